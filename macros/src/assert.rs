@@ -0,0 +1,111 @@
+//! Implementation of `#[assert_impls]`.
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Error, GenericParam, Generics, Ident, Item, Token, Type,
+};
+
+/// Expands the attribute `args` on `item`.
+pub fn expand(
+    args: TokenStream,
+    item: TokenStream,
+) -> syn::Result<TokenStream> {
+    let (expr, types) = split_args(args)?;
+    let (ident, generics) = match syn::parse2::<Item>(item.clone())? {
+        Item::Struct(item) => (item.ident, item.generics),
+        Item::Enum(item) => (item.ident, item.generics),
+        Item::Union(item) => (item.ident, item.generics),
+        Item::Type(item) => (item.ident, item.generics),
+        other => {
+            return Err(Error::new(
+                other.span(),
+                "`#[assert_impls]` can only be applied to structs, enums, \
+                 unions, and type aliases",
+            ))
+        }
+    };
+
+    let types = match types {
+        Some(types) => types,
+        None => vec![self_type(&ident, &generics)?],
+    };
+
+    let checks = types.iter().map(|ty| {
+        quote_spanned! {ty.span()=>
+            const _: [(); 1] = [(); ::impls::impls!(#ty: #expr) as usize];
+        }
+    });
+
+    Ok(quote! {
+        #item
+        #(#checks)*
+    })
+}
+
+/// Splits `args` into the trait expression and the instantiations after `;`,
+/// if any.
+fn split_args(
+    args: TokenStream,
+) -> syn::Result<(TokenStream, Option<Vec<Type>>)> {
+    let mut tokens = args.clone().into_iter();
+    let expr: TokenStream = tokens
+        .by_ref()
+        .take_while(|tt| match tt {
+            TokenTree::Punct(p) => p.as_char() != ';',
+            _ => true,
+        })
+        .collect();
+
+    if expr.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "expected a logical trait expression, such as `Send & Sync`",
+        ));
+    }
+
+    let rest: TokenStream = tokens.collect();
+    if rest.is_empty() {
+        return Ok((expr, None));
+    }
+
+    let types = Punctuated::<Type, Token![,]>::parse_terminated.parse2(rest)?;
+    if types.is_empty() {
+        return Err(Error::new(
+            args.span(),
+            "expected instantiations to check after `;`",
+        ));
+    }
+    Ok((expr, Some(types.into_iter().collect())))
+}
+
+/// Returns the annotated type itself, with any lifetimes set to `'static`.
+fn self_type(ident: &Ident, generics: &Generics) -> syn::Result<Type> {
+    let mut lifetimes = Vec::new();
+    for param in &generics.params {
+        match param {
+            GenericParam::Lifetime(_) => {
+                lifetimes.push(quote!('static));
+            }
+            GenericParam::Type(_) | GenericParam::Const(_) => {
+                return Err(Error::new(
+                    generics.span(),
+                    format!(
+                        "generic types must list the instantiations to \
+                         check, such as `#[assert_impls(Send; {}<u8>)]`",
+                        ident,
+                    ),
+                ));
+            }
+        }
+    }
+
+    if lifetimes.is_empty() {
+        Ok(parse_quote!(#ident))
+    } else {
+        Ok(parse_quote!(#ident<#(#lifetimes),*>))
+    }
+}
@@ -0,0 +1,397 @@
+//! Implementation of `#[derive(ImplsFields)]` and the macros that read its
+//! output.
+//!
+//! The derive emits a `macro_rules!` macro next to the type that passes every
+//! field type to a callback macro, along with the kind of item and the names of
+//! any unit variants. Generic parameters become metavariables, so
+//! field types are instantiated with the arguments written at the call site.
+
+use proc_macro2::{Delimiter, Group, Spacing, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+
+use crate::printer::{metavar, Printer};
+use syn::spanned::Spanned;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericArgument, GenericParam, Ident,
+    PathArguments, Type,
+};
+
+/// Returns the name of the macro generated for the type named `ident`.
+fn macro_name(ident: &Ident) -> Ident {
+    format_ident!("__impls_fields_{}", ident, span = ident.span())
+}
+
+/// A field of a struct, union, or enum variant.
+struct Field {
+    variant: Option<String>,
+    name: String,
+    ty: Type,
+}
+
+/// Expands `#[derive(ImplsFields)]` on `input`.
+pub fn derive(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(input)?;
+
+    let mut fields = Vec::new();
+    let kind = match input.data {
+        Data::Struct(ref data) => {
+            push_fields(&mut fields, None, &data.fields);
+            quote!(struct)
+        }
+        Data::Enum(ref data) => {
+            // Unit variants are listed since they have no fields.
+            let mut units = Vec::new();
+            for variant in &data.variants {
+                let name = variant.ident.to_string();
+                if variant.fields.is_empty() {
+                    units.push(quote!([#name]));
+                }
+                push_fields(&mut fields, Some(name), &variant.fields);
+            }
+            quote!(enum #(#units)*)
+        }
+        Data::Union(ref data) => {
+            for field in &data.fields.named {
+                fields.push(Field {
+                    variant: None,
+                    name: field.ident.as_ref().unwrap().to_string(),
+                    ty: field.ty.clone(),
+                });
+            }
+            quote!(union)
+        }
+    };
+
+    // Each parameter is bound to the metavariable `$__impls_<index>`, and its
+    // name as written to `$__impls_<index>_name`.
+    let self_var = metavar(&format_ident!("__impls_self"));
+    let mut matchers = Vec::new();
+    let mut params = Vec::new();
+    let mut only_lifetimes = true;
+
+    for (i, param) in input.generics.params.iter().enumerate() {
+        let var = metavar(&format_ident!("__impls_{}", i));
+        let name = metavar(&format_ident!("__impls_{}_name", i));
+        let matcher = |kind| quote!([#var:#kind] #name:literal);
+        match param {
+            GenericParam::Lifetime(param) => {
+                matchers.push(matcher(quote!(lifetime)));
+                params.push(Param::Lifetime(param.lifetime.ident.clone(), var));
+            }
+            GenericParam::Type(param) => {
+                only_lifetimes = false;
+                matchers.push(matcher(quote!(ty)));
+                params.push(Param::Ident(param.ident.clone(), var));
+            }
+            GenericParam::Const(param) => {
+                only_lifetimes = false;
+                matchers.push(matcher(quote!(expr)));
+                params.push(Param::Ident(param.ident.clone(), var));
+            }
+        }
+    }
+
+    let body = |params: &[Param]| {
+        let fields = fields.iter().map(|field| {
+            let variant = match field.variant {
+                Some(ref variant) => quote!([#variant]),
+                None => quote!([]),
+            };
+            let name = &field.name;
+            let tokens =
+                substitute(field.ty.to_token_stream(), params, &self_var);
+            let ty_name = display(tokens.clone());
+            quote!([#variant #name, #ty_name => #tokens])
+        });
+        quote! {
+            $($cb)*! { $args [#self_var] [#kind] [#(#fields)*] }
+        }
+    };
+
+    let main = body(&params);
+    let mut rules = quote! {
+        ([$($cb:tt)*] $args:tt [#self_var:ty] [#(#matchers),* $(,)?]) => {
+            #main
+        };
+    };
+
+    // Lifetimes may be omitted if there are no other parameters.
+    if !params.is_empty() && only_lifetimes {
+        let statics: Vec<Param> = params
+            .iter()
+            .map(|param| match param {
+                Param::Lifetime(ident, _) | Param::Ident(ident, _) => {
+                    Param::Lifetime(ident.clone(), quote!('static))
+                }
+            })
+            .collect();
+        let body = body(&statics);
+        rules.extend(quote! {
+            ([$($cb:tt)*] $args:tt [#self_var:ty] []) => {
+                #body
+            };
+        });
+    }
+
+    let name = macro_name(&input.ident);
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            #rules
+        }
+    })
+}
+
+/// Appends the fields of a struct or enum variant.
+fn push_fields(
+    list: &mut Vec<Field>,
+    variant: Option<String>,
+    fields: &Fields,
+) {
+    for (i, field) in fields.iter().enumerate() {
+        let name = match field.ident {
+            Some(ref ident) => ident.to_string(),
+            None => i.to_string(),
+        };
+        list.push(Field {
+            variant: variant.clone(),
+            name,
+            ty: field.ty.clone(),
+        });
+    }
+}
+
+/// A generic parameter and the tokens that replace it.
+enum Param {
+    Lifetime(Ident, TokenStream),
+    Ident(Ident, TokenStream),
+}
+
+/// Replaces generic parameters and `Self` in `tokens`.
+fn substitute(
+    tokens: TokenStream,
+    params: &[Param],
+    self_var: &TokenStream,
+) -> TokenStream {
+    let mut output = TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), params, self_var);
+                let mut new = Group::new(group.delimiter(), stream);
+                new.set_span(group.span());
+                output.extend(Some(TokenTree::Group(new)));
+            }
+            TokenTree::Punct(ref p) if p.as_char() == '\'' => {
+                let replacement = match tokens.peek() {
+                    Some(TokenTree::Ident(ident)) => {
+                        params.iter().find_map(|param| match param {
+                            Param::Lifetime(name, tokens) if name == ident => {
+                                Some(tokens.clone())
+                            }
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                };
+                match replacement {
+                    Some(replacement) => {
+                        tokens.next();
+                        output.extend(replacement);
+                    }
+                    None => output.extend(Some(token)),
+                }
+            }
+            TokenTree::Ident(ref ident) if ident == "Self" => {
+                output.extend(self_var.clone());
+            }
+            TokenTree::Ident(ref ident) => {
+                let replacement = params.iter().find_map(|param| match param {
+                    Param::Ident(name, tokens) if name == ident => {
+                        Some(tokens.clone())
+                    }
+                    _ => None,
+                });
+                match replacement {
+                    Some(replacement) => output.extend(replacement),
+                    None => output.extend(Some(token)),
+                }
+            }
+            _ => output.extend(Some(token)),
+        }
+    }
+    output
+}
+
+/// Returns an expression for the name of a type, with the names of any
+/// metavariables filled in.
+fn display(tokens: TokenStream) -> TokenStream {
+    let mut printer = Printer::new();
+    printer.write(tokens);
+
+    let text = printer.text;
+    let mut pieces = printer.pieces;
+    if pieces.is_empty() {
+        return quote!(#text);
+    }
+    if !text.is_empty() {
+        pieces.push(quote!(#text));
+    }
+    quote!(concat!(#(#pieces),*))
+}
+
+/// Expands `_impls_fields!([callback] args Type)` into a call to the macro
+/// generated for `Type`.
+pub fn dispatch(input: TokenStream) -> syn::Result<TokenStream> {
+    let mut tokens = input.into_iter();
+    let (callback, args) = match (tokens.next(), tokens.next()) {
+        (Some(callback), Some(args)) => (callback, args),
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "expected a callback, arguments, and a type",
+            ))
+        }
+    };
+
+    let ty_tokens: TokenStream = tokens.collect();
+    let ty: Type = syn::parse2(ty_tokens.clone())?;
+
+    let mut inner = &ty;
+    loop {
+        match inner {
+            Type::Group(group) => inner = &group.elem,
+            Type::Paren(paren) => inner = &paren.elem,
+            _ => break,
+        }
+    }
+
+    let segment =
+        match inner {
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.segments.last().unwrap()
+            }
+            _ => return Err(Error::new(
+                ty.span(),
+                "expected a struct, enum, or union that derives `ImplsFields`",
+            )),
+        };
+
+    if let PathArguments::AngleBracketed(ref generics) = segment.arguments {
+        for arg in &generics.args {
+            match arg {
+                GenericArgument::Lifetime(_)
+                | GenericArgument::Type(_)
+                | GenericArgument::Const(_) => {}
+                other => {
+                    return Err(Error::new(
+                        other.span(),
+                        "expected a lifetime, type, or constant argument",
+                    ))
+                }
+            }
+        }
+    }
+
+    // Arguments are passed as written, along with their names.
+    let args_list = raw_args(ty_tokens.clone()).into_iter().map(|arg| {
+        let mut printer = Printer::new();
+        printer.write(arg.clone());
+        let name = printer.text;
+        quote!([#arg] #name)
+    });
+
+    let name = macro_name(&segment.ident);
+    let ty_group = Group::new(Delimiter::Bracket, ty_tokens);
+    Ok(quote! {
+        #name! { #callback #args #ty_group [#(#args_list),*] }
+    })
+}
+
+/// Returns whether `tokens[i]` is a `>` that closes angle brackets, rather
+/// than the end of `->`.
+fn is_close(tokens: &[TokenTree], i: usize) -> bool {
+    match tokens[i] {
+        TokenTree::Punct(ref p) if p.as_char() == '>' => match i.checked_sub(1)
+        {
+            Some(prev) => match tokens[prev] {
+                TokenTree::Punct(ref p) => {
+                    !(p.as_char() == '-' && p.spacing() == Spacing::Joint)
+                }
+                _ => true,
+            },
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+/// Returns whether `token` is the punctuation `c`.
+fn is_punct(token: &TokenTree, c: char) -> bool {
+    match token {
+        TokenTree::Punct(p) => p.as_char() == c,
+        _ => false,
+    }
+}
+
+/// Returns the tokens of each generic argument of the last path segment in
+/// `tokens`, which must be a path type.
+fn raw_args(tokens: TokenStream) -> Vec<TokenStream> {
+    let mut tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    // Unwrap invisible and parenthesized groups around the path.
+    while tokens.len() == 1 {
+        let inner = match tokens[0] {
+            TokenTree::Group(ref group)
+                if group.delimiter() == Delimiter::None
+                    || group.delimiter() == Delimiter::Parenthesis =>
+            {
+                group.stream()
+            }
+            _ => break,
+        };
+        tokens = inner.into_iter().collect();
+    }
+
+    let last = match tokens.len().checked_sub(1) {
+        Some(last) if is_close(&tokens, last) => last,
+        _ => return Vec::new(),
+    };
+
+    // Find the `<` that matches the final `>`.
+    let mut depth = 0;
+    let mut open = last;
+    for i in (0..=last).rev() {
+        if is_close(&tokens, i) {
+            depth += 1;
+        } else if is_punct(&tokens[i], '<') {
+            depth -= 1;
+            if depth == 0 {
+                open = i;
+                break;
+            }
+        }
+    }
+
+    let mut args = Vec::new();
+    let mut arg = TokenStream::new();
+    let mut depth = 0;
+    for i in open + 1..last {
+        if is_close(&tokens, i) {
+            depth -= 1;
+        } else if is_punct(&tokens[i], '<') {
+            depth += 1;
+        } else if depth == 0 && is_punct(&tokens[i], ',') {
+            args.push(std::mem::take(&mut arg));
+            continue;
+        }
+        arg.extend(Some(tokens[i].clone()));
+    }
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+    args
+}
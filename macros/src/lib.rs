@@ -0,0 +1,185 @@
+//! Procedural macros for [`impls`](https://docs.rs/impls).
+//!
+//! These are re-exported by `impls` when its `macros` feature is enabled and
+//! should be used through it:
+//!
+//! ```toml
+//! [dependencies]
+//! impls = { version = "1", features = ["macros"] }
+//! ```
+//!
+//! Generated code refers to `::impls`, so the crate must not be renamed.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+
+mod assert;
+mod fields;
+mod printer;
+mod sweep;
+
+use proc_macro::TokenStream;
+
+/// Asserts at compile-time that the annotated type implements a logical trait
+/// expression.
+///
+/// The expression uses the same grammar as [`impls!`]. The item is emitted
+/// unchanged, followed by a constant that fails to compile if the expression
+/// evaluates to `false`.
+///
+/// This can be applied to structs, enums, unions, and type aliases.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate impls;
+/// use impls::assert_impls;
+///
+/// #[assert_impls(Send & Sync & !Copy)]
+/// pub struct Pool {
+///     connections: Vec<String>,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Types that are not `Sync` fail to compile:
+///
+/// ```compile_fail
+/// # extern crate impls;
+/// use impls::assert_impls;
+/// use std::cell::Cell;
+///
+/// #[assert_impls(Send & Sync)]
+/// pub struct Counter {
+///     count: Cell<usize>,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// # Generic Types
+///
+/// Generic types must list the instantiations to check after a `;`:
+///
+/// ```
+/// # extern crate impls;
+/// use impls::assert_impls;
+/// use std::rc::Rc;
+///
+/// #[assert_impls(Clone & Send; Holder<u8>, Holder<String>)]
+/// #[assert_impls(Clone & !Send; Holder<Rc<u8>>)]
+/// #[derive(Clone)]
+/// pub struct Holder<T>(T);
+/// # fn main() {}
+/// ```
+///
+/// Lifetime parameters can be omitted if the type has no other parameters, in
+/// which case `'static` is used:
+///
+/// ```
+/// # extern crate impls;
+/// use impls::assert_impls;
+///
+/// #[assert_impls(Copy & Send)]
+/// #[derive(Clone, Copy)]
+/// pub struct Name<'a>(&'a str);
+/// # fn main() {}
+/// ```
+///
+/// [`impls!`]: https://docs.rs/impls/1/impls/macro.impls.html
+#[proc_macro_attribute]
+pub fn assert_impls(args: TokenStream, item: TokenStream) -> TokenStream {
+    assert::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Lets [`explain_fields!`], [`audit_auto_traits!`], and
+/// [`derive_eligibility!`] evaluate traits over the types of every field.
+///
+/// This generates a hidden `macro_rules!` macro next to the type, so macros
+/// that read fields must be used after the type definition in the same module
+/// or its children. Two types with the same name must not derive this in the
+/// same scope.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use impls::ImplsFields;
+/// use std::rc::Rc;
+///
+/// #[derive(ImplsFields)]
+/// struct Conn<T> {
+///     id: u64,
+///     cache: Rc<T>,
+/// }
+///
+/// # fn main() {
+/// let fields = explain_fields!(Conn<u8>: Send);
+/// let offending: Vec<_> = fields.offending().map(|f| f.name).collect();
+/// assert_eq!(offending, ["cache"]);
+/// # }
+/// ```
+///
+/// [`explain_fields!`]: https://docs.rs/impls/1/impls/macro.explain_fields.html
+/// [`audit_auto_traits!`]: https://docs.rs/impls/1/impls/macro.audit_auto_traits.html
+/// [`derive_eligibility!`]: https://docs.rs/impls/1/impls/macro.derive_eligibility.html
+#[proc_macro_derive(ImplsFields)]
+pub fn derive_impls_fields(input: TokenStream) -> TokenStream {
+    fields::derive(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Returns the [`&'static Sweep`] of a logical trait expression over a range
+/// of const values or arities, reporting where its value changes.
+///
+/// The variable after the expression is replaced by each value in the type
+/// and the expression. Tuples and function pointers are swept by arity:
+///
+/// - `tuples(T) RANGE: EXPR` sweeps over `()`, `(T,)`, `(T, T)`, and so on.
+/// - `fns(T) RANGE: EXPR` sweeps over `fn()`, `fn(T)`, `fn(T, T)`, and so on,
+///   with an optional return type, as in `fns(T) -> R RANGE: EXPR`.
+///
+/// Ranges are written as `START..=END` or `START..END`, and can have at most
+/// 1024 values, since each value instantiates a type. Larger ranges are a
+/// compile error.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::hash::Hash;
+///
+/// let sweep = impls_sweep!([u8; N]: Default, N in 0..=64);
+/// assert_eq!(sweep.changes().collect::<Vec<_>>(), [33]);
+///
+/// let sweep = impls_sweep!(tuples(u8) 0..=16: Hash);
+/// assert_eq!(sweep.to_string(), "\
+/// tuples(u8): Hash
+///   arity in 0..=12: true
+///   arity in 13..=16: false");
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate impls;
+/// let sweep = impls_sweep!([u8; N]: Default, N in 0..=1024);
+/// ```
+///
+/// [`&'static Sweep`]: https://docs.rs/impls/1/impls/sweep/struct.Sweep.html
+#[proc_macro]
+pub fn impls_sweep(input: TokenStream) -> TokenStream {
+    sweep::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Passes the fields of a type that derives `ImplsFields` to a callback.
+#[doc(hidden)]
+#[proc_macro]
+pub fn _impls_fields(input: TokenStream) -> TokenStream {
+    fields::dispatch(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,146 @@
+//! Formatting of tokens as they are conventionally written.
+
+use proc_macro2::{Delimiter, Punct, Spacing, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+/// Returns `$name`.
+pub fn metavar(name: &Ident) -> TokenStream {
+    let dollar = Punct::new('$', Spacing::Alone);
+    quote!(#dollar #name)
+}
+
+/// The previous token written by `Printer`.
+#[derive(Clone, Copy, PartialEq)]
+enum Prev {
+    Start,
+    Word,
+    Open,
+    Close,
+    Punct(char),
+    /// A binary operator of a logical trait expression.
+    Op,
+}
+
+/// Writes types as they are conventionally formatted, such as `Rc<str>`
+/// rather than `Rc < str >`.
+pub struct Printer {
+    /// Literal text and the names of metavariables.
+    pub pieces: Vec<TokenStream>,
+    /// Text written since the last piece.
+    pub text: String,
+    prev: Prev,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Printer {
+            pieces: Vec::new(),
+            text: String::new(),
+            prev: Prev::Start,
+        }
+    }
+
+    /// Returns `tokens` as they are conventionally written, such as
+    /// `Level<N>: Send & !Sync`.
+    pub fn text(tokens: TokenStream) -> String {
+        let mut printer = Printer::new();
+        printer.write(tokens);
+        printer.text
+    }
+
+    /// Writes a space if `next` should be separated from the previous token.
+    fn space(&mut self, next: Prev) {
+        let space = match (self.prev, next) {
+            (Prev::Start, _) | (Prev::Open, _) | (_, Prev::Close) => false,
+            (Prev::Op, _) | (_, Prev::Op) => true,
+            (_, Prev::Punct(',')) | (_, Prev::Punct(';')) => false,
+            (Prev::Punct(c), _) if ",;+=".contains(c) => true,
+            (_, Prev::Punct(c)) if "+=-".contains(c) => true,
+            (Prev::Punct('>'), Prev::Word) => true,
+            (Prev::Word, Prev::Word) | (Prev::Close, Prev::Word) => true,
+            (Prev::Word, Prev::Open) => self.text.ends_with("mut"),
+            _ => false,
+        };
+        if space {
+            self.text.push(' ');
+        }
+        self.prev = next;
+    }
+
+    pub fn write(&mut self, tokens: TokenStream) {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::None => ("", ""),
+                    };
+                    self.space(Prev::Open);
+                    self.text.push_str(open);
+                    self.write(group.stream());
+                    self.space(Prev::Close);
+                    self.text.push_str(close);
+                }
+                TokenTree::Punct(ref p) if p.as_char() == '$' => {
+                    // A metavariable from `substitute`.
+                    self.space(Prev::Word);
+                    if !self.text.is_empty() {
+                        let text = std::mem::take(&mut self.text);
+                        self.pieces.push(quote!(#text));
+                    }
+                    match tokens.next() {
+                        Some(TokenTree::Ident(ref var))
+                            if var == "__impls_self" =>
+                        {
+                            self.pieces.push(quote!(stringify!(#p #var)));
+                        }
+                        Some(TokenTree::Ident(var)) => {
+                            self.pieces
+                                .push(metavar(&format_ident!("{}_name", var)));
+                        }
+                        _ => {}
+                    }
+                }
+                TokenTree::Punct(ref p) if p.as_char() == '\'' => {
+                    self.space(Prev::Word);
+                    self.text.push('\'');
+                    if let Some(TokenTree::Ident(ident)) = tokens.next() {
+                        self.text.push_str(&ident.to_string());
+                    }
+                }
+                TokenTree::Punct(p) => {
+                    // The `>` in `->` is spaced like an operator.
+                    let c = p.as_char();
+                    let operand = matches!(
+                        self.prev,
+                        Prev::Word | Prev::Close | Prev::Punct('>')
+                    );
+                    if c == '>' && self.prev == Prev::Punct('-') {
+                        self.text.push('>');
+                        self.prev = Prev::Punct('=');
+                    } else if "&|^".contains(c) && operand {
+                        // `&` after an operand is an operator rather than a
+                        // reference.
+                        self.space(Prev::Op);
+                        self.text.push(c);
+                    } else {
+                        self.space(Prev::Punct(c));
+                        self.text.push(c);
+                    }
+                }
+                TokenTree::Ident(ident) => {
+                    self.space(Prev::Word);
+                    self.text.push_str(&ident.to_string());
+                }
+                TokenTree::Literal(literal) => {
+                    self.space(Prev::Word);
+                    self.text.push_str(&literal.to_string());
+                }
+            }
+        }
+    }
+}
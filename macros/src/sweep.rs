@@ -0,0 +1,319 @@
+//! Implementation of `impls_sweep!`.
+
+use proc_macro2::{
+    Delimiter, Group, Literal, Punct, Spacing, Span, TokenStream, TokenTree,
+};
+use quote::quote;
+use syn::{Error, Ident};
+
+use crate::printer::Printer;
+
+/// The most values that a sweep can be over, since each instantiates a type
+/// and evaluates the expression on it.
+const MAX_VALUES: i128 = 1024;
+
+/// The types that a sweep instantiates for each value.
+enum Pattern {
+    /// A type with a variable, such as `[u8; N]`.
+    Var(Ident, TokenStream),
+    /// Tuples of an element type, such as `(u8, u8)`.
+    Tuples(TokenStream),
+    /// Function pointers taking parameters of a type, such as `fn(u8, u8)`,
+    /// with an optional return type.
+    Fns(TokenStream, Option<TokenStream>),
+}
+
+impl Pattern {
+    /// Returns the type for `value`.
+    fn instantiate(&self, value: i128) -> TokenStream {
+        match self {
+            Pattern::Var(var, ty) => substitute(ty.clone(), var, value),
+            Pattern::Tuples(elem) => {
+                let elems = (0..value).map(|_| elem);
+                quote!((#(#elems,)*))
+            }
+            Pattern::Fns(param, output) => {
+                let params = (0..value).map(|_| param);
+                let output = output.as_ref().map(|ty| quote!(-> #ty));
+                quote!(fn(#(#params),*) #output)
+            }
+        }
+    }
+}
+
+/// Expands `impls_sweep!` on `input`.
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let keyword = match (tokens.first(), tokens.get(1)) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)))
+            if group.delimiter() == Delimiter::Parenthesis =>
+        {
+            Some(ident.to_string())
+        }
+        _ => None,
+    };
+
+    match keyword.as_deref() {
+        Some("tuples") | Some("fns") => expand_arities(&tokens),
+        _ => expand_var(&tokens),
+    }
+}
+
+/// Expands `Type: EXPR, VAR in RANGE`.
+fn expand_var(tokens: &[TokenTree]) -> syn::Result<TokenStream> {
+    let colon = find_colon(tokens)
+        .ok_or_else(|| error(tokens, "expected `Type: EXPR, VAR in RANGE`"))?;
+    let (ty, rest) = (&tokens[..colon], &tokens[colon + 1..]);
+
+    let comma = (0..rest.len())
+        .rev()
+        .find(|&i| {
+            is_punct(&rest[i], ',')
+                && matches!(rest.get(i + 1), Some(TokenTree::Ident(_)))
+                && matches!(
+                    rest.get(i + 2),
+                    Some(TokenTree::Ident(ident)) if ident == "in"
+                )
+        })
+        .ok_or_else(|| error(rest, "expected `, VAR in RANGE` at the end"))?;
+    let expr = &rest[..comma];
+    let var = match &rest[comma + 1] {
+        TokenTree::Ident(ident) => ident.clone(),
+        _ => unreachable!(),
+    };
+    let (start, end) = parse_range(&rest[comma + 3..])?;
+
+    if ty.is_empty() || expr.is_empty() {
+        return Err(error(tokens, "expected `Type: EXPR, VAR in RANGE`"));
+    }
+
+    let ty: TokenStream = ty.iter().cloned().collect();
+    let expr: TokenStream = expr.iter().cloned().collect();
+    let name = var.to_string();
+    let pattern = Pattern::Var(var, ty.clone());
+    Ok(output(&ty, &name, &expr, &pattern, start, end))
+}
+
+/// Expands `tuples(Type) RANGE: EXPR` and `fns(Type) [-> Type] RANGE: EXPR`.
+fn expand_arities(tokens: &[TokenTree]) -> syn::Result<TokenStream> {
+    let (keyword, elem) = match (&tokens[0], &tokens[1]) {
+        (TokenTree::Ident(ident), TokenTree::Group(group)) => {
+            (ident.to_string(), group.stream())
+        }
+        _ => unreachable!(),
+    };
+    if elem.is_empty() {
+        return Err(error(&tokens[1..2], "expected an element type"));
+    }
+
+    let colon = find_colon(tokens).ok_or_else(|| {
+        error(tokens, "expected `: EXPR` after the range of arities")
+    })?;
+    let (head, expr) = (&tokens[2..colon], &tokens[colon + 1..]);
+    if expr.is_empty() {
+        return Err(error(tokens, "expected a logical trait expression"));
+    }
+
+    // The range starts at the first literal or lone `-` before the colon.
+    let range_start = head
+        .iter()
+        .position(|token| match token {
+            TokenTree::Literal(_) => true,
+            _ => is_punct(token, '-') && !is_punct_joint(token),
+        })
+        .ok_or_else(|| error(tokens, "expected a range of arities"))?;
+    let (output_ty, range) = head.split_at(range_start);
+    let (start, end) = parse_range(range)?;
+    if start < 0 {
+        return Err(error(range, "arities cannot be negative"));
+    }
+
+    let pattern = if keyword == "tuples" {
+        if !output_ty.is_empty() {
+            return Err(error(output_ty, "tuples do not have a return type"));
+        }
+        Pattern::Tuples(elem)
+    } else {
+        let output_ty = match output_ty {
+            [] => None,
+            [dash, gt, ty @ ..]
+                if is_punct(dash, '-')
+                    && is_punct(gt, '>')
+                    && !ty.is_empty() =>
+            {
+                Some(ty.iter().cloned().collect())
+            }
+            _ => return Err(error(output_ty, "expected `-> Type`")),
+        };
+        Pattern::Fns(elem, output_ty)
+    };
+
+    let ty: TokenStream = tokens[..2 + range_start].iter().cloned().collect();
+    let expr: TokenStream = expr.iter().cloned().collect();
+    Ok(output(&ty, "arity", &expr, &pattern, start, end))
+}
+
+/// Returns the constant `Sweep` over `start..=end`.
+fn output(
+    ty: &TokenStream,
+    var: &str,
+    expr: &TokenStream,
+    pattern: &Pattern,
+    start: i128,
+    end: i128,
+) -> TokenStream {
+    let points = (start..=end).map(|value| {
+        let ty = pattern.instantiate(value);
+        let expr = match pattern {
+            Pattern::Var(var, _) => substitute(expr.clone(), var, value),
+            _ => expr.clone(),
+        };
+        let value = Literal::i128_unsuffixed(value);
+        quote! {
+            ::impls::sweep::Point {
+                value: #value,
+                implements: ::impls::impls!(#ty: #expr),
+            }
+        }
+    });
+
+    let ty = Printer::text(ty.clone());
+    let expr = Printer::text(expr.clone());
+    quote! {{
+        const SWEEP: &::impls::sweep::Sweep<'static> = &::impls::sweep::Sweep {
+            ty: #ty,
+            expr: #expr,
+            var: #var,
+            points: &[#(#points),*],
+        };
+        SWEEP
+    }}
+}
+
+/// Replaces `var` in `tokens` with `value`.
+fn substitute(tokens: TokenStream, var: &Ident, value: i128) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), var, value);
+                let mut new = Group::new(group.delimiter(), stream);
+                new.set_span(group.span());
+                TokenTree::Group(new)
+            }
+            TokenTree::Ident(ref ident) if ident == var => {
+                let mut literal = Literal::i128_unsuffixed(value.abs());
+                literal.set_span(ident.span());
+                if value < 0 {
+                    // Negative const arguments must be in braces.
+                    let minus = Punct::new('-', Spacing::Alone);
+                    let stream = quote!(#minus #literal);
+                    TokenTree::Group(Group::new(Delimiter::Brace, stream))
+                } else {
+                    TokenTree::Literal(literal)
+                }
+            }
+            token => token,
+        })
+        .collect()
+}
+
+/// Parses `START..=END` or `START..END` into an inclusive range.
+fn parse_range(tokens: &[TokenTree]) -> syn::Result<(i128, i128)> {
+    let mut i = 0;
+    let start = parse_int(tokens, &mut i)?;
+
+    let inclusive = match (tokens.get(i), tokens.get(i + 1), tokens.get(i + 2))
+    {
+        (Some(a), Some(b), Some(c))
+            if is_punct(a, '.') && is_punct(b, '.') && is_punct(c, '=') =>
+        {
+            i += 3;
+            true
+        }
+        (Some(a), Some(b), _) if is_punct(a, '.') && is_punct(b, '.') => {
+            i += 2;
+            false
+        }
+        _ => return Err(error(tokens, "expected a range, such as `0..=32`")),
+    };
+
+    let end = parse_int(tokens, &mut i)?;
+    if i != tokens.len() {
+        return Err(error(&tokens[i..], "unexpected tokens after the range"));
+    }
+
+    let end = if inclusive { end } else { end - 1 };
+    if start > end {
+        return Err(error(tokens, "the range is empty"));
+    }
+    if !matches!(end.checked_sub(start), Some(len) if len < MAX_VALUES) {
+        let message = format!(
+            "the range has more than {} values, the most a sweep can be over",
+            MAX_VALUES,
+        );
+        return Err(error(tokens, &message));
+    }
+    Ok((start, end))
+}
+
+/// Parses an integer literal at `tokens[*i]`, with an optional `-`.
+fn parse_int(tokens: &[TokenTree], i: &mut usize) -> syn::Result<i128> {
+    let negative = matches!(tokens.get(*i), Some(t) if is_punct(t, '-'));
+    if negative {
+        *i += 1;
+    }
+    match tokens.get(*i) {
+        Some(TokenTree::Literal(literal)) => {
+            *i += 1;
+            let value =
+                syn::LitInt::from(literal.clone()).base10_parse::<i128>()?;
+            Ok(if negative { -value } else { value })
+        }
+        _ => Err(error(tokens, "expected an integer, such as `0..=32`")),
+    }
+}
+
+/// Returns the index of the first `:` that is not part of `::`.
+fn find_colon(tokens: &[TokenTree]) -> Option<usize> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if is_punct(&tokens[i], ':') {
+            if is_punct_joint(&tokens[i])
+                && matches!(tokens.get(i + 1), Some(t) if is_punct(t, ':'))
+            {
+                i += 2;
+                continue;
+            }
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn is_punct(token: &TokenTree, c: char) -> bool {
+    match token {
+        TokenTree::Punct(p) => p.as_char() == c,
+        _ => false,
+    }
+}
+
+fn is_punct_joint(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Punct(p) => p.spacing() == Spacing::Joint,
+        _ => false,
+    }
+}
+
+/// Returns an error spanning `tokens`.
+fn error(tokens: &[TokenTree], message: &str) -> Error {
+    let span = match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => first
+            .span()
+            .join(last.span())
+            .unwrap_or_else(|| first.span()),
+        _ => Span::call_site(),
+    };
+    Error::new(span, message)
+}
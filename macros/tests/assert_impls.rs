@@ -0,0 +1,37 @@
+use impls::assert_impls;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+#[assert_impls(Send & !Sync)]
+pub struct Counter {
+    _count: Cell<usize>,
+}
+
+#[assert_impls(Copy & Send; Pair<u8, u16>, Pair<char, ()>)]
+#[assert_impls(!Copy & Send; Pair<String, u8>)]
+#[derive(Clone, Copy)]
+pub struct Pair<A, B>(A, B);
+
+#[assert_impls(Copy)]
+#[derive(Clone, Copy)]
+pub enum Name<'a> {
+    Borrowed(&'a str),
+    Unknown,
+}
+
+#[assert_impls(Default & Unpin; Alias<Vec<u8>>)]
+pub type Alias<T> = PhantomData<T>;
+
+#[test]
+fn items_are_unchanged() {
+    let _ = Counter {
+        _count: Cell::new(0),
+    };
+    let Pair(a, b) = Pair(1u8, 2u16);
+    assert_eq!((a, b), (1, 2));
+    match Name::Borrowed("a") {
+        Name::Borrowed(name) => assert_eq!(name, "a"),
+        Name::Unknown => unreachable!(),
+    }
+    let _: Alias<Vec<u8>> = PhantomData;
+}
@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate impls;
+
+use impls::ImplsFields;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(ImplsFields)]
+pub struct Conn<'a, T, const N: usize> {
+    name: &'a str,
+    buf: [T; N],
+    next: Option<Box<Self>>,
+}
+
+#[derive(ImplsFields)]
+pub struct Counter(u64, Cell<u64>);
+
+#[derive(ImplsFields)]
+pub enum Event<'a> {
+    Empty,
+    Shared(Rc<str>),
+    Borrowed { text: &'a str, mut_ptr: *mut u8 },
+}
+
+#[test]
+fn generic_struct() {
+    let report = explain_fields!(Conn<'static, Rc<u8>, 4>: Send);
+    assert!(!report.value);
+    assert_eq!(
+        report.to_string(),
+        "\
+Conn<'static, Rc<u8>, 4>: Send = false
+  name: &'static str = true
+  buf: [Rc<u8>; 4] = false (offending)
+  next: Option<Box<Conn<'static, Rc<u8>, 4>>> = false (offending)"
+    );
+
+    let report = explain_fields!(Conn<'static, u8, 4>: Send & Sync);
+    assert!(report.value);
+    assert_eq!(report.offending().count(), 0);
+}
+
+#[test]
+fn tuple_struct_and_enum() {
+    let report = explain_fields!(Counter: Sync);
+    let offending: Vec<_> =
+        report.offending().map(|f| (f.to_string(), f.ty)).collect();
+    assert_eq!(offending, [("1".to_string(), "Cell<u64>")]);
+
+    // Lifetimes default to `'static` when omitted.
+    let report = explain_fields!(Event: Send);
+    let offending: Vec<_> = report
+        .offending()
+        .map(|f| (f.variant, f.name, f.ty))
+        .collect();
+    assert_eq!(
+        offending,
+        [
+            (Some("Shared"), "0", "Rc<str>"),
+            (Some("Borrowed"), "mut_ptr", "*mut u8"),
+        ]
+    );
+}
+
+#[derive(ImplsFields)]
+pub struct Pinned {
+    raw: *const u8,
+    _pin: std::marker::PhantomPinned,
+}
+
+unsafe impl Send for Pinned {}
+impl Unpin for Pinned {}
+
+#[derive(ImplsFields)]
+pub struct Handle<T>(T);
+
+unsafe impl<T: Copy> Send for Handle<T> {}
+
+#[test]
+fn auto_traits() {
+    let audit = audit_auto_traits!(Pinned);
+    let overridden: Vec<_> = audit.overridden().map(|t| t.name).collect();
+    assert_eq!(overridden, ["Send", "Unpin"]);
+
+    let text = audit.to_string();
+    assert!(text.starts_with(
+        "\
+Pinned:
+  Send = true, structurally false (overridden)
+    raw: *const u8
+  Sync = false
+    raw: *const u8
+  Unpin = true, structurally false (overridden)
+    _pin: std::marker::PhantomPinned"
+    ));
+
+    // A bound on the manual impl withholds the trait.
+    let audit = audit_auto_traits!(Handle<String>);
+    let send = audit.get("Send").unwrap();
+    assert!(!send.actual && send.structural() && send.is_overridden());
+    assert_eq!(audit_auto_traits!(Handle<u8>).overridden().count(), 0);
+}
+
+#[derive(ImplsFields)]
+pub enum Shape {
+    Point,
+    Circle(u32),
+    Polygon(Vec<(i32, i32)>),
+}
+
+#[derive(ImplsFields)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+}
+
+#[derive(ImplsFields)]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+#[test]
+fn eligibility() {
+    let shape = derive_eligibility!(Shape);
+    let achievable: Vec<_> = shape.achievable().collect();
+    assert_eq!(
+        achievable,
+        [
+            "Clone",
+            "Debug",
+            "PartialEq",
+            "Eq",
+            "Hash",
+            "Default",
+            "PartialOrd",
+            "Ord"
+        ]
+    );
+
+    let value = derive_eligibility!(Value);
+    assert_eq!(
+        value.to_string(),
+        "\
+Value:
+  Clone = true
+  Copy = true
+  Debug = true
+  PartialEq = true
+  Eq = false
+    Float.0: f64
+  Hash = false
+    Float.0: f64
+  Default = false (requires a unit variant marked `#[default]`)
+  PartialOrd = true
+  Ord = false
+    Float.0: f64"
+    );
+
+    let bits = derive_eligibility!(Bits);
+    let achievable: Vec<_> = bits.achievable().collect();
+    assert_eq!(achievable, ["Clone", "Copy"]);
+    assert_eq!(bits.get("Debug").unwrap().unmet, Some("a struct or enum"));
+}
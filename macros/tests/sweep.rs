@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate impls;
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+struct Level<const N: i32>;
+
+impl Clone for Level<-1> {
+    fn clone(&self) -> Self {
+        Level
+    }
+}
+
+impl Clone for Level<0> {
+    fn clone(&self) -> Self {
+        Level
+    }
+}
+
+#[test]
+fn arrays() {
+    let sweep = impls_sweep!([u8; N]: Default & Debug, N in 30..36);
+    assert_eq!(
+        sweep.to_string(),
+        "[u8; N]: Default & Debug
+  N in 30..=32: true
+  N in 33..=35: false"
+    );
+    assert_eq!(sweep.get(32), Some(true));
+    assert_eq!(sweep.get(36), None);
+
+    // The variable is also replaced in the expression.
+    let sweep = impls_sweep!(
+        [u8; N]: PartialEq<[u8; N]> & !PartialEq<[u8; 2]>, N in 1..=3
+    );
+    assert_eq!(sweep.changes().collect::<Vec<_>>(), [2, 3]);
+}
+
+#[test]
+fn const_values() {
+    let sweep = impls_sweep!(Level<N>: Clone, N in -2..=2);
+    assert_eq!(
+        sweep.to_string(),
+        "Level<N>: Clone
+  N = -2: false
+  N in -1..=0: true
+  N in 1..=2: false"
+    );
+    assert_eq!(sweep.changes().collect::<Vec<_>>(), [-1, 1]);
+}
+
+#[test]
+fn arities() {
+    let sweep = impls_sweep!(tuples(u8) 0..=16: Hash);
+    assert_eq!(sweep.changes().collect::<Vec<_>>(), [13]);
+    assert_eq!(sweep.var, "arity");
+
+    let sweep = impls_sweep!(fns(u8) -> u8 0..=3: Copy & Debug);
+    assert_eq!(
+        sweep.to_string(),
+        "fns(u8) -> u8: Copy & Debug\n  arity in 0..=3: true"
+    );
+}
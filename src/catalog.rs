@@ -0,0 +1,340 @@
+//! A curated catalog of standard library traits.
+//!
+//! The catalog is used by [`trait_profile!`] and [`impls_diff!`]. Traits are
+//! grouped by the crate that defines them:
+//!
+//! - [`Group::Core`]: always available.
+//!   - Auto traits: `Send`, `Sync`, `Unpin`.
+//!   - Markers: `Sized`, `Copy`, `Any`.
+//!   - Derivable traits: `Clone`, `Default`, `Debug`, `PartialEq`, `Eq`,
+//!     `PartialOrd`, `Ord`, `Hash`.
+//!   - Formatting traits: `Display`, `Binary`, `Octal`, `LowerHex`,
+//!     `UpperHex`, `LowerExp`, `UpperExp`, `Pointer`.
+//!   - Conversions: `FromStr`, `Into<T>` for `bool`, `char`, `u64`, `i64`,
+//!     `u128`, `i128`, and `f64`.
+//!   - Borrowing: `AsRef<T>` and `Borrow<T>` for `str` and `[u8]`, `Deref`,
+//!     `DerefMut`.
+//!   - Iteration: `Iterator`, `IntoIterator`, `DoubleEndedIterator`,
+//!     `ExactSizeIterator`, `FusedIterator`.
+//!   - Operators: `Add`, `Sub`, `Mul`, `Div`, `Rem`, `Neg`, `Not`, `BitAnd`,
+//!     `BitOr`, `BitXor`.
+//!   - Asynchrony: `Future`.
+//! - [`Group::Alloc`]: enabled by the `alloc` feature.
+//!   - `ToOwned`, `ToString`, `Into<T>` for `String`, `Vec<u8>`, and
+//!     `Box<str>`.
+//! - [`Group::Std`]: enabled by the `std` feature.
+//!   - Unwind safety: `UnwindSafe`, `RefUnwindSafe`.
+//!   - Errors: `Error`.
+//!   - I/O: `Read`, `Write`, `BufRead`, `Seek`, `ToSocketAddrs`.
+//!   - Paths: `AsRef<T>` for `Path` and `OsStr`, `Into<T>` for `PathBuf` and
+//!     `OsString`.
+//!
+//! Generic traits without arguments use `Self`, such as `PartialEq` meaning
+//! `PartialEq<Self>`.
+//!
+//! [`trait_profile!`]: ../macro.trait_profile.html
+//! [`impls_diff!`]: ../macro.impls_diff.html
+//! [`Group::Core`]: enum.Group.html#variant.Core
+//! [`Group::Alloc`]: enum.Group.html#variant.Alloc
+//! [`Group::Std`]: enum.Group.html#variant.Std
+
+/// The crate that defines a trait in the catalog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Group {
+    /// Traits from `core`.
+    Core,
+    /// Traits from `alloc`, enabled by the `alloc` feature.
+    Alloc,
+    /// Traits from `std`, enabled by the `std` feature.
+    Std,
+}
+
+/// A trait in the catalog and whether a type implements it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The trait's name, including any generic arguments.
+    pub name: &'static str,
+    /// The crate that defines the trait.
+    pub group: Group,
+    /// Whether the type implements the trait.
+    pub value: bool,
+}
+
+/// Which traits in the catalog a type implements, returned by
+/// [`trait_profile!`].
+///
+/// [`trait_profile!`]: ../macro.trait_profile.html
+#[derive(Clone, Copy, Debug)]
+pub struct Profile<'a> {
+    /// The type as written.
+    pub ty: &'static str,
+    /// Every trait in the catalog, in catalog order.
+    pub entries: &'a [Entry],
+}
+
+impl<'a> Profile<'a> {
+    /// Returns the names of the traits that the type implements.
+    pub fn implemented(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.entries.iter().filter(|e| e.value).map(|e| e.name)
+    }
+
+    /// Returns the names of the traits that the type does not implement.
+    pub fn not_implemented(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.entries.iter().filter(|e| !e.value).map(|e| e.name)
+    }
+
+    /// Returns whether the type implements the trait named `name`, or `None`
+    /// if it is not in the catalog.
+    pub fn get(&self, name: &str) -> Option<bool> {
+        self.entries
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.value)
+    }
+}
+
+/// Operators whose right-hand side defaults to `Self`.
+///
+/// These cannot be bounds of `?Sized` types directly, since `Self` must be
+/// `Sized` to be a type argument. Each trait here adds that requirement.
+#[doc(hidden)]
+pub mod _ops {
+    use _core::ops;
+
+    macro_rules! sized_ops {
+        ($($op:ident)+) => {$(
+            pub trait $op: Sized + ops::$op {}
+            impl<T: ops::$op> $op for T {}
+        )+};
+    }
+
+    sized_ops! { Add Sub Mul Div Rem BitAnd BitOr BitXor }
+}
+
+/// Returns a [`&'static Profile`] of which traits in the
+/// [catalog](catalog/index.html) a type implements.
+///
+/// Traits from `alloc` and `std` are only included when the corresponding
+/// features are enabled.
+///
+/// Because the profile is a constant, the type must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// let profile = trait_profile!(u8);
+///
+/// assert_eq!(profile.get("Copy"), Some(true));
+/// assert_eq!(profile.get("Into<u64>"), Some(true));
+/// assert_eq!(profile.get("Iterator"), Some(false));
+///
+/// let traits: Vec<_> = trait_profile!(std::ops::Range<u8>)
+///     .implemented()
+///     .filter(|t| t.contains("Iterator"))
+///     .collect();
+///
+/// assert_eq!(traits, [
+///     "Iterator",
+///     "IntoIterator",
+///     "DoubleEndedIterator",
+///     "ExactSizeIterator",
+///     "FusedIterator",
+/// ]);
+/// ```
+///
+/// [`&'static Profile`]: catalog/struct.Profile.html
+#[macro_export(local_inner_macros)]
+macro_rules! trait_profile {
+    ($type:ty) => {
+        _impls_catalog!(_trait_profile[$type])
+    };
+}
+
+/// Builds the output of `trait_profile` from the catalog.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _trait_profile {
+    ($type:ty [$([$group:ident $name:expr => $($trait:tt)+])*]) => {{
+        const PROFILE: &$crate::catalog::Profile<'static> =
+            &$crate::catalog::Profile {
+                ty: _impls_stringify!($type),
+                entries: &[$($crate::catalog::Entry {
+                    name: $name,
+                    group: _impls_catalog!(@group $group),
+                    value: _impls!(@bool $type: $($trait)+),
+                }),*],
+            };
+        PROFILE
+    }};
+}
+
+/// Passes the catalog of standard traits to the `$cb` macro after `$args`.
+///
+/// Each trait is passed as `[group label => path]`, where the path is
+/// absolute.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_catalog {
+    (@group core) => {
+        $crate::catalog::Group::Core
+    };
+    (@group alloc) => {
+        $crate::catalog::Group::Alloc
+    };
+    (@group std) => {
+        $crate::catalog::Group::Std
+    };
+    ($cb:ident [$($args:tt)*]) => {
+        _impls_catalog_alloc!($cb [$($args)*] [
+            // Auto traits.
+            [core "Send" => $crate::_core::marker::Send]
+            [core "Sync" => $crate::_core::marker::Sync]
+            [core "Unpin" => $crate::_core::marker::Unpin]
+
+            // Markers.
+            [core "Sized" => $crate::_core::marker::Sized]
+            [core "Copy" => $crate::_core::marker::Copy]
+            [core "Any" => $crate::_core::any::Any]
+
+            // Derivable traits.
+            [core "Clone" => $crate::_core::clone::Clone]
+            [core "Default" => $crate::_core::default::Default]
+            [core "Debug" => $crate::_core::fmt::Debug]
+            [core "PartialEq" => $crate::_core::cmp::PartialEq]
+            [core "Eq" => $crate::_core::cmp::Eq]
+            [core "PartialOrd" => $crate::_core::cmp::PartialOrd]
+            [core "Ord" => $crate::_core::cmp::Ord]
+            [core "Hash" => $crate::_core::hash::Hash]
+
+            // Formatting traits.
+            [core "Display" => $crate::_core::fmt::Display]
+            [core "Binary" => $crate::_core::fmt::Binary]
+            [core "Octal" => $crate::_core::fmt::Octal]
+            [core "LowerHex" => $crate::_core::fmt::LowerHex]
+            [core "UpperHex" => $crate::_core::fmt::UpperHex]
+            [core "LowerExp" => $crate::_core::fmt::LowerExp]
+            [core "UpperExp" => $crate::_core::fmt::UpperExp]
+            [core "Pointer" => $crate::_core::fmt::Pointer]
+
+            // Conversions.
+            [core "FromStr" => $crate::_core::str::FromStr]
+            [core "Into<bool>" => $crate::_core::convert::Into<bool>]
+            [core "Into<char>" => $crate::_core::convert::Into<char>]
+            [core "Into<u64>" => $crate::_core::convert::Into<u64>]
+            [core "Into<i64>" => $crate::_core::convert::Into<i64>]
+            [core "Into<u128>" => $crate::_core::convert::Into<u128>]
+            [core "Into<i128>" => $crate::_core::convert::Into<i128>]
+            [core "Into<f64>" => $crate::_core::convert::Into<f64>]
+
+            // Borrowing.
+            [core "AsRef<str>" => $crate::_core::convert::AsRef<str>]
+            [core "AsRef<[u8]>" => $crate::_core::convert::AsRef<[u8]>]
+            [core "Borrow<str>" => $crate::_core::borrow::Borrow<str>]
+            [core "Borrow<[u8]>" => $crate::_core::borrow::Borrow<[u8]>]
+            [core "Deref" => $crate::_core::ops::Deref]
+            [core "DerefMut" => $crate::_core::ops::DerefMut]
+
+            // Iteration.
+            [core "Iterator" => $crate::_core::iter::Iterator]
+            [core "IntoIterator" => $crate::_core::iter::IntoIterator]
+            [core "DoubleEndedIterator" =>
+                $crate::_core::iter::DoubleEndedIterator]
+            [core "ExactSizeIterator" => $crate::_core::iter::ExactSizeIterator]
+            [core "FusedIterator" => $crate::_core::iter::FusedIterator]
+
+            // Operators.
+            [core "Add" => $crate::catalog::_ops::Add]
+            [core "Sub" => $crate::catalog::_ops::Sub]
+            [core "Mul" => $crate::catalog::_ops::Mul]
+            [core "Div" => $crate::catalog::_ops::Div]
+            [core "Rem" => $crate::catalog::_ops::Rem]
+            [core "Neg" => $crate::_core::ops::Neg]
+            [core "Not" => $crate::_core::ops::Not]
+            [core "BitAnd" => $crate::catalog::_ops::BitAnd]
+            [core "BitOr" => $crate::catalog::_ops::BitOr]
+            [core "BitXor" => $crate::catalog::_ops::BitXor]
+
+            // Asynchrony.
+            [core "Future" => $crate::_core::future::Future]
+        ])
+    };
+}
+
+/// Appends traits from `alloc` to the catalog if the `alloc` feature is
+/// enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_catalog_alloc {
+    ($cb:ident $args:tt [$($core:tt)*]) => {
+        _impls_catalog_std!($cb $args [$($core)*
+            [alloc "ToOwned" => $crate::_alloc::borrow::ToOwned]
+            [alloc "ToString" => $crate::_alloc::string::ToString]
+            [alloc "Into<String>" =>
+                $crate::_core::convert::Into<$crate::_alloc::string::String>]
+            [alloc "Into<Vec<u8>>" =>
+                $crate::_core::convert::Into<$crate::_alloc::vec::Vec<u8>>]
+            [alloc "Into<Box<str>>" =>
+                $crate::_core::convert::Into<$crate::_alloc::boxed::Box<str>>]
+        ])
+    };
+}
+
+/// Appends traits from `alloc` to the catalog if the `alloc` feature is
+/// enabled.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_catalog_alloc {
+    ($cb:ident $args:tt $catalog:tt) => {
+        _impls_catalog_std!($cb $args $catalog)
+    };
+}
+
+/// Appends traits from `std` to the catalog and passes it to `$cb`, if the
+/// `std` feature is enabled.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_catalog_std {
+    ($cb:ident [$($args:tt)*] [$($catalog:tt)*]) => {
+        $cb!($($args)* [$($catalog)*
+            // Unwind safety.
+            [std "UnwindSafe" => $crate::_std::panic::UnwindSafe]
+            [std "RefUnwindSafe" => $crate::_std::panic::RefUnwindSafe]
+
+            // Errors.
+            [std "Error" => $crate::_std::error::Error]
+
+            // I/O.
+            [std "Read" => $crate::_std::io::Read]
+            [std "Write" => $crate::_std::io::Write]
+            [std "BufRead" => $crate::_std::io::BufRead]
+            [std "Seek" => $crate::_std::io::Seek]
+            [std "ToSocketAddrs" => $crate::_std::net::ToSocketAddrs]
+
+            // Paths.
+            [std "AsRef<Path>" =>
+                $crate::_core::convert::AsRef<$crate::_std::path::Path>]
+            [std "AsRef<OsStr>" =>
+                $crate::_core::convert::AsRef<$crate::_std::ffi::OsStr>]
+            [std "Into<PathBuf>" =>
+                $crate::_core::convert::Into<$crate::_std::path::PathBuf>]
+            [std "Into<OsString>" =>
+                $crate::_core::convert::Into<$crate::_std::ffi::OsString>]
+        ])
+    };
+}
+
+/// Appends traits from `std` to the catalog and passes it to `$cb`, if the
+/// `std` feature is enabled.
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_catalog_std {
+    ($cb:ident [$($args:tt)*] $catalog:tt) => {
+        $cb!($($args)* $catalog)
+    };
+}
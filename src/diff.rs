@@ -0,0 +1,213 @@
+//! Comparisons of which traits two types implement.
+//!
+//! Use [`impls_diff!`] to compare types and [`assert_same_traits!`] to require
+//! that they implement the same traits.
+//!
+//! [`impls_diff!`]: ../macro.impls_diff.html
+//! [`assert_same_traits!`]: ../macro.assert_same_traits.html
+
+use _core::fmt;
+
+/// The traits that each of two types implement, returned by [`impls_diff!`].
+///
+/// Its [`Display`] implementation groups traits by which types implement
+/// them, one group per line:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// struct UserId(u64);
+///
+/// let text = impls_diff!(u64, UserId).to_string();
+/// let lines: Vec<&str> = text.lines().collect();
+///
+/// assert_eq!(lines[0], "`u64` vs `UserId`:");
+/// assert!(lines[1].starts_with("  only `u64`: Copy, Default, PartialOrd,"));
+/// assert!(lines[1].contains("Binary, Octal, LowerHex, UpperHex,"));
+/// assert_eq!(lines[2], "  only `UserId`: (none)");
+/// assert!(lines[3].starts_with("  both: Send, Sync, Unpin, Sized, Any,"));
+/// ```
+///
+/// [`impls_diff!`]: ../macro.impls_diff.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct Diff<'a> {
+    /// The first type as written.
+    pub a: &'static str,
+    /// The second type as written.
+    pub b: &'static str,
+    /// Every compared trait.
+    pub traits: &'a [Compared],
+}
+
+/// Whether each of two types implements a trait.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Compared {
+    /// The trait's name.
+    pub name: &'static str,
+    /// Whether the first type implements the trait.
+    pub a: bool,
+    /// Whether the second type implements the trait.
+    pub b: bool,
+}
+
+impl<'a> Diff<'a> {
+    /// Returns `true` if both types implement the same traits.
+    pub fn is_same(&self) -> bool {
+        self.traits.iter().all(|t| t.a == t.b)
+    }
+
+    /// Returns the traits that only the first type implements.
+    pub fn only_a(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.filter(|t| t.a && !t.b)
+    }
+
+    /// Returns the traits that only the second type implements.
+    pub fn only_b(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.filter(|t| !t.a && t.b)
+    }
+
+    /// Returns the traits that both types implement.
+    pub fn both(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.filter(|t| t.a && t.b)
+    }
+
+    fn filter<F>(&self, f: F) -> impl Iterator<Item = &'static str> + 'a
+    where
+        F: Fn(&Compared) -> bool + 'a,
+    {
+        self.traits.iter().filter(move |t| f(t)).map(|t| t.name)
+    }
+}
+
+impl<'a> fmt::Display for Diff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn list<I>(f: &mut fmt::Formatter, names: I) -> fmt::Result
+        where
+            I: Iterator<Item = &'static str>,
+        {
+            let mut names = names.peekable();
+            if names.peek().is_none() {
+                return f.write_str("(none)");
+            }
+            for (i, name) in names.enumerate() {
+                if i != 0 {
+                    f.write_str(", ")?;
+                }
+                f.write_str(name)?;
+            }
+            Ok(())
+        }
+
+        write!(f, "`{}` vs `{}`:", self.a, self.b)?;
+        write!(f, "\n  only `{}`: ", self.a)?;
+        list(f, self.only_a())?;
+        write!(f, "\n  only `{}`: ", self.b)?;
+        list(f, self.only_b())?;
+        f.write_str("\n  both: ")?;
+        list(f, self.both())
+    }
+}
+
+/// Returns a [`&'static Diff`] of which traits each of two types implement.
+///
+/// Types are compared over the [catalog](catalog/index.html) of standard
+/// traits, followed by any traits listed after a `;`. This is useful for
+/// verifying that a newtype kept every trait of the type it wraps.
+///
+/// Because the diff is a constant, types must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// trait Id {}
+/// impl Id for u64 {}
+///
+/// #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// struct UserId(u64);
+///
+/// let diff = impls_diff!(u64, UserId; Id, From<u64>);
+///
+/// assert!(diff.only_a().any(|t| t == "Copy"));
+/// assert!(diff.only_a().any(|t| t == "Id"));
+/// assert!(diff.both().any(|t| t == "Hash"));
+/// assert_eq!(diff.only_b().count(), 0);
+/// ```
+///
+/// [`&'static Diff`]: diff/struct.Diff.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_diff {
+    ($a:ty, $b:ty $(; $($traits:tt)*)?) => {
+        _impls_split!(_impls_diff [@user [$a] [$b]] $($($traits)*)?)
+    };
+}
+
+/// Asserts at runtime that two types implement the same traits, as compared
+/// by [`impls_diff!`](macro.impls_diff.html).
+///
+/// On failure, this panics with the diff.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// struct Byte(u8);
+///
+/// assert_same_traits!(Byte, (u8,));
+/// ```
+///
+/// A missing trait fails:
+///
+/// ```should_panic
+/// # #[macro_use] extern crate impls;
+/// #[derive(Clone, Debug)]
+/// struct UserId(u64);
+///
+/// assert_same_traits!(u64, UserId);
+/// ```
+#[macro_export(local_inner_macros)]
+macro_rules! assert_same_traits {
+    ($($input:tt)+) => {{
+        let diff = impls_diff!($($input)+);
+        if !diff.is_same() {
+            $crate::_core::panic!("types implement different traits: {}", diff);
+        }
+    }};
+}
+
+/// Builds the output of `impls_diff` from split user traits.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_diff {
+    (@user $a:tt $b:tt [$(($($user:tt)+))*]) => {
+        _impls_catalog!(_impls_diff [
+            @build $a $b
+            [$([_impls_stringify!($($user)+) => $($user)+])*]
+        ])
+    };
+    (
+        @build [$a:ty] [$b:ty]
+        [$([$user_name:expr => $($user:tt)+])*]
+        [$([$group:ident $name:expr => $($trait:tt)+])*]
+    ) => {{
+        const DIFF: &$crate::diff::Diff<'static> = &$crate::diff::Diff {
+            a: _impls_stringify!($a),
+            b: _impls_stringify!($b),
+            traits: &[
+                $(_impls_diff!(@compare [$a] [$b] $name => $($trait)+),)*
+                $(_impls_diff!(@compare [$a] [$b] $user_name => $($user)+),)*
+            ],
+        };
+        DIFF
+    }};
+    (@compare [$a:ty] [$b:ty] $name:expr => $($trait:tt)+) => {
+        $crate::diff::Compared {
+            name: $name,
+            a: _impls!(@bool $a: $($trait)+),
+            b: _impls!(@bool $b: $($trait)+),
+        }
+    };
+}
@@ -0,0 +1,451 @@
+//! Evaluation trees of logical trait expressions.
+//!
+//! These are built from the same parse as [`impls!`], so the grouping of
+//! operators in a tree is exactly the grouping used to compute the `bool`.
+//!
+//! Use [`impls_explain!`] to get the tree of an expression over a type, or
+//! [`explain_type!`] to get the tree of a type's generic arguments over an
+//! expression.
+//!
+//! [`impls!`]: ../macro.impls.html
+//! [`impls_explain!`]: ../macro.impls_explain.html
+//! [`explain_type!`]: ../macro.explain_type.html
+
+use _core::fmt;
+
+/// A node in the evaluation tree of a logical trait expression.
+#[derive(Clone, Copy, Debug)]
+pub enum Node<'a> {
+    /// A single trait.
+    Leaf(Leaf),
+    /// Not (`!`).
+    Not(&'a Node<'a>),
+    /// Or (`|`).
+    Or(&'a Node<'a>, &'a Node<'a>),
+    /// And (`&`).
+    And(&'a Node<'a>, &'a Node<'a>),
+    /// Exclusive-or (`^`).
+    Xor(&'a Node<'a>, &'a Node<'a>),
+}
+
+/// A trait within a logical trait expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Leaf {
+    /// The trait as written, such as `From<u8>`.
+    pub name: &'static str,
+    /// Whether the type implements the trait.
+    pub value: bool,
+}
+
+impl<'a> Node<'a> {
+    /// Returns the value of the expression rooted at this node.
+    pub fn value(&self) -> bool {
+        match *self {
+            Node::Leaf(leaf) => leaf.value,
+            Node::Not(a) => !a.value(),
+            Node::Or(a, b) => a.value() | b.value(),
+            Node::And(a, b) => a.value() & b.value(),
+            Node::Xor(a, b) => a.value() ^ b.value(),
+        }
+    }
+
+    /// Returns the name of the operator at this node, or the trait if this is
+    /// a leaf.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Node::Leaf(leaf) => leaf.name,
+            Node::Not(_) => "not",
+            Node::Or(..) => "or",
+            Node::And(..) => "and",
+            Node::Xor(..) => "xor",
+        }
+    }
+
+    /// Returns the operands of this node, from left to right.
+    pub fn children(&self) -> [Option<&'a Node<'a>>; 2] {
+        match *self {
+            Node::Leaf(_) => [None, None],
+            Node::Not(a) => [Some(a), None],
+            Node::Or(a, b) | Node::And(a, b) | Node::Xor(a, b) => {
+                [Some(a), Some(b)]
+            }
+        }
+    }
+
+    /// Calls `f` on every leaf, from left to right.
+    pub fn for_each_leaf<F: FnMut(&Leaf)>(&self, mut f: F) {
+        fn walk(node: &Node, f: &mut dyn FnMut(&Leaf)) {
+            match *node {
+                Node::Leaf(ref leaf) => f(leaf),
+                Node::Not(a) => walk(a, f),
+                Node::Or(a, b) | Node::And(a, b) | Node::Xor(a, b) => {
+                    walk(a, f);
+                    walk(b, f);
+                }
+            }
+        }
+        walk(self, &mut f);
+    }
+
+    /// Returns the operands and operator symbol if this is a binary node.
+    fn binary(&self) -> Option<(&'a Node<'a>, &'static str, &'a Node<'a>)> {
+        match *self {
+            Node::Or(a, b) => Some((a, "|", b)),
+            Node::And(a, b) => Some((a, "&", b)),
+            Node::Xor(a, b) => Some((a, "^", b)),
+            Node::Leaf(_) | Node::Not(_) => None,
+        }
+    }
+
+    /// Writes the expression with explicit grouping, using `leaf` to write
+    /// each trait.
+    fn write_grouped(
+        &self,
+        f: &mut fmt::Formatter,
+        leaf: fn(&Leaf, &mut fmt::Formatter) -> fmt::Result,
+        nested: bool,
+    ) -> fmt::Result {
+        match *self {
+            Node::Leaf(ref l) => leaf(l, f),
+            Node::Not(a) => {
+                f.write_str("!")?;
+                a.write_grouped(f, leaf, true)
+            }
+            _ => {
+                // Always `Some` since other variants are handled above.
+                let (a, op, b) = self.binary().unwrap();
+                if nested {
+                    f.write_str("(")?;
+                }
+                a.write_grouped(f, leaf, true)?;
+                write!(f, " {} ", op)?;
+                b.write_grouped(f, leaf, true)?;
+                if nested {
+                    f.write_str(")")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes `node` as an indented tree, where bit `i` of `open` is set if the
+/// branch at depth `i` has more children below.
+fn write_tree(
+    node: &Node,
+    f: &mut fmt::Formatter,
+    labels: &[(&'static str, &'static str)],
+    depth: u32,
+    open: u64,
+) -> fmt::Result {
+    write!(f, "{} = {}", node.name(), node.value())?;
+    if let Node::Leaf(ref leaf) = *node {
+        if let Some(label) = find_label(labels, leaf.name) {
+            write!(f, " ({})", label)?;
+        }
+    }
+
+    let children = node.children();
+    let count = children.iter().filter(|c| c.is_some()).count();
+
+    for (i, child) in children.iter().filter_map(|&c| c).enumerate() {
+        let open = write_branch(f, depth, open, i + 1 == count)?;
+        write_tree(child, f, labels, depth + 1, open)?;
+    }
+    Ok(())
+}
+
+/// Starts a new line with the branch to a child at `depth`, and returns the
+/// `open` bits for the child's own children.
+fn write_branch(
+    f: &mut fmt::Formatter,
+    depth: u32,
+    open: u64,
+    last: bool,
+) -> Result<u64, fmt::Error> {
+    f.write_str("\n")?;
+    for level in 0..depth {
+        let is_open = open.checked_shr(level).unwrap_or(0) & 1 != 0;
+        f.write_str(if is_open { "│   " } else { "    " })?;
+    }
+    f.write_str(if last { "└── " } else { "├── " })?;
+
+    Ok(if last {
+        open
+    } else {
+        open | 1u64.checked_shl(depth).unwrap_or(0)
+    })
+}
+
+/// Returns the label for the trait `name`, ignoring whitespace differences
+/// in how either was written.
+fn find_label(
+    labels: &[(&'static str, &'static str)],
+    name: &str,
+) -> Option<&'static str> {
+    fn non_space(s: &str) -> impl Iterator<Item = char> + '_ {
+        s.chars().filter(|c| !c.is_whitespace())
+    }
+
+    labels
+        .iter()
+        .find(|&&(leaf, _)| non_space(leaf).eq(non_space(name)))
+        .map(|&(_, label)| label)
+}
+
+impl<'a> fmt::Display for Node<'a> {
+    /// Writes the tree rooted at this node, one node per line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_tree(self, f, &[], 0, 0)
+    }
+}
+
+/// The evaluation tree of a type over a logical trait expression, returned by
+/// [`impls_explain!`].
+///
+/// Its [`Display`] implementation renders the expression, followed by its
+/// tree annotated with the value of every node and the label of every leaf:
+///
+/// ```text
+/// String: Clone & !Copy = true
+/// and = true
+/// ├── Clone = true (duplicable)
+/// └── not = true
+///     └── Copy = false
+/// ```
+///
+/// [`impls_explain!`]: ../macro.impls_explain.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct Explanation<'a> {
+    /// The type as written.
+    pub ty: &'static str,
+    /// The logical trait expression as written.
+    pub expr: &'static str,
+    /// The root of the evaluation tree.
+    pub root: &'a Node<'a>,
+    /// Pairs of traits as written and their user-provided labels.
+    pub labels: &'a [(&'static str, &'static str)],
+}
+
+impl<'a> Explanation<'a> {
+    /// Returns the value of the whole expression.
+    pub fn value(&self) -> bool {
+        self.root.value()
+    }
+
+    /// Returns the user-provided label of `leaf`, if any.
+    pub fn label(&self, leaf: &Leaf) -> Option<&'static str> {
+        find_label(self.labels, leaf.name)
+    }
+
+    /// Renders this explanation into `w`.
+    ///
+    /// This is the same as the [`Display`] output and does not allocate,
+    /// which makes it usable in `no_std` environments.
+    ///
+    /// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+    pub fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "{}", self)
+    }
+}
+
+impl<'a> fmt::Display for Explanation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: {} = {}", self.ty, self.expr, self.value())?;
+        write_tree(self.root, f, self.labels, 0, 0)
+    }
+}
+
+/// The failure report of [`assert_impls_rt!`].
+///
+/// This lists every trait in the expression with whether it is implemented,
+/// followed by how operators grouped them and the resulting values.
+///
+/// [`assert_impls_rt!`]: ../macro.assert_impls_rt.html
+#[derive(Clone, Copy, Debug)]
+pub struct Report<'a> {
+    ty: &'static str,
+    expr: &'static str,
+    root: &'a Node<'a>,
+}
+
+impl<'a> Report<'a> {
+    /// Creates a report for `ty` over the trait expression `expr` that was
+    /// parsed into `root`.
+    pub fn new(
+        ty: &'static str,
+        expr: &'static str,
+        root: &'a Node<'a>,
+    ) -> Self {
+        Report { ty, expr, root }
+    }
+}
+
+impl<'a> fmt::Display for Report<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn write_leaves(
+            node: &Node,
+            f: &mut fmt::Formatter,
+            width: usize,
+        ) -> fmt::Result {
+            match *node {
+                Node::Leaf(leaf) => writeln!(
+                    f,
+                    "  {:width$} = {}",
+                    leaf.name,
+                    leaf.value,
+                    width = width,
+                ),
+                Node::Not(a) => write_leaves(a, f, width),
+                Node::Or(a, b) | Node::And(a, b) | Node::Xor(a, b) => {
+                    write_leaves(a, f, width)?;
+                    write_leaves(b, f, width)
+                }
+            }
+        }
+
+        let mut width = 0;
+        self.root.for_each_leaf(|leaf| {
+            width = width.max(leaf.name.len());
+        });
+
+        writeln!(f, "assertion failed: `{}: {}`", self.ty, self.expr)?;
+        write_leaves(self.root, f, width)?;
+
+        f.write_str("  parsed: ")?;
+        self.root
+            .write_grouped(f, |l, f| f.write_str(l.name), false)?;
+        f.write_str("\n    with: ")?;
+        self.root
+            .write_grouped(f, |l, f| write!(f, "{}", l.value), false)?;
+        write!(f, " = {}", self.root.value())
+    }
+}
+
+/// A type in the tree of generic arguments built by [`explain_type!`].
+///
+/// [`explain_type!`]: ../macro.explain_type.html
+#[derive(Clone, Copy, Debug)]
+pub struct TypeNode<'a> {
+    /// The type as written.
+    pub ty: &'static str,
+    /// Whether the type satisfies the expression.
+    pub value: bool,
+    /// The type arguments of this type, including the elements of tuples,
+    /// arrays, and slices, and the referent of references and pointers.
+    pub args: &'a [TypeNode<'a>],
+}
+
+impl<'a> TypeNode<'a> {
+    /// Calls `f` on every node, parents before children.
+    pub fn for_each<F: FnMut(&TypeNode)>(&self, mut f: F) {
+        fn walk(node: &TypeNode, f: &mut dyn FnMut(&TypeNode)) {
+            f(node);
+            for arg in node.args {
+                walk(arg, f);
+            }
+        }
+        walk(self, &mut f);
+    }
+
+    /// Returns `true` if this type has `value` but none of its arguments do.
+    ///
+    /// This only looks at this type and its arguments. See
+    /// [`TypeExplanation::for_each_flip_point`] for the types responsible for
+    /// the value of the whole type.
+    ///
+    /// [`TypeExplanation::for_each_flip_point`]: struct.TypeExplanation.html#method.for_each_flip_point
+    pub fn is_flip_point(&self, value: bool) -> bool {
+        self.value == value && self.args.iter().all(|arg| arg.value != value)
+    }
+}
+
+/// The tree of a type's generic arguments, each evaluated over a logical trait
+/// expression, returned by [`explain_type!`].
+///
+/// Its [`Display`] implementation renders the tree and marks the flip points,
+/// which are where the answer flips:
+///
+/// ```text
+/// Arc<Mutex<Vec<Rc<u8>>>>: Send = false
+/// └── Mutex<Vec<Rc<u8>>> = false
+///     └── Vec<Rc<u8>> = false
+///         └── Rc<u8> = false (flips)
+///             └── u8 = true
+/// ```
+///
+/// [`explain_type!`]: ../macro.explain_type.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct TypeExplanation<'a> {
+    /// The logical trait expression as written.
+    pub expr: &'static str,
+    /// The type as written, with its arguments.
+    pub root: &'a TypeNode<'a>,
+}
+
+impl<'a> TypeExplanation<'a> {
+    /// Returns the value of the expression over the whole type.
+    pub fn value(&self) -> bool {
+        self.root.value
+    }
+
+    /// Calls `f` on each flip point, parents before children.
+    ///
+    /// A flip point has the same value as the whole type, but none of its
+    /// arguments do, and is reached only through types with that value. There
+    /// are none if every type in the tree has the same value.
+    pub fn for_each_flip_point<F: FnMut(&TypeNode)>(&self, mut f: F) {
+        fn walk(node: &TypeNode, value: bool, f: &mut dyn FnMut(&TypeNode)) {
+            if node.is_flip_point(value) {
+                f(node);
+            }
+            for arg in node.args.iter().filter(|arg| arg.value == value) {
+                walk(arg, value, f);
+            }
+        }
+        if self.flips() {
+            walk(self.root, self.value(), &mut f);
+        }
+    }
+
+    /// Returns `true` if any type in the tree has a different value than the
+    /// whole type.
+    fn flips(&self) -> bool {
+        let value = self.value();
+        let mut flips = false;
+        self.root.for_each(|node| flips |= node.value != value);
+        flips
+    }
+}
+
+impl<'a> fmt::Display for TypeExplanation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn write_node(
+            node: &TypeNode,
+            f: &mut fmt::Formatter,
+            value: bool,
+            // Whether flip points can be within this node.
+            flips: bool,
+            depth: u32,
+            open: u64,
+        ) -> fmt::Result {
+            if flips && node.is_flip_point(value) {
+                f.write_str(" (flips)")?;
+            }
+            let count = node.args.len();
+            for (i, arg) in node.args.iter().enumerate() {
+                let open = write_branch(f, depth, open, i + 1 == count)?;
+                write!(f, "{} = {}", arg.ty, arg.value)?;
+                let flips = flips && arg.value == value;
+                write_node(arg, f, value, flips, depth + 1, open)?;
+            }
+            Ok(())
+        }
+
+        let root = self.root;
+        write!(f, "{}: {} = {}", root.ty, self.expr, root.value)?;
+        write_node(root, f, root.value, self.flips(), 0, 0)
+    }
+}
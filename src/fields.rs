@@ -0,0 +1,569 @@
+//! Evaluations of logical trait expressions over the fields of a type.
+//!
+//! Use [`explain_fields!`], [`audit_auto_traits!`], or [`derive_eligibility!`]
+//! on a type that derives [`ImplsFields`]. This module requires the `macros`
+//! feature.
+//!
+//! [`explain_fields!`]: ../macro.explain_fields.html
+//! [`audit_auto_traits!`]: ../macro.audit_auto_traits.html
+//! [`derive_eligibility!`]: ../macro.derive_eligibility.html
+//! [`ImplsFields`]: ../derive.ImplsFields.html
+
+use _core::fmt;
+
+/// A field of a struct, union, or enum variant, and whether its type satisfies
+/// a logical trait expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field {
+    /// The enum variant that contains the field, if any.
+    pub variant: Option<&'static str>,
+    /// The field's name, or its index in a tuple struct or variant.
+    pub name: &'static str,
+    /// The field's type, with generic arguments filled in.
+    pub ty: &'static str,
+    /// Whether the field's type satisfies the expression.
+    pub value: bool,
+}
+
+impl fmt::Display for Field {
+    /// Writes the field's path, such as `Variant.0` or `name`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(variant) = self.variant {
+            write!(f, "{}.", variant)?;
+        }
+        f.write_str(self.name)
+    }
+}
+
+/// The evaluation of a logical trait expression over a type and each of its
+/// fields, returned by [`explain_fields!`].
+///
+/// Its [`Display`] implementation lists every field and marks the ones whose
+/// types do not satisfy the expression:
+///
+/// ```text
+/// Conn: Send & Sync = false
+///   id: u64 = true
+///   cache: Rc<u8> = false (offending)
+/// ```
+///
+/// [`explain_fields!`]: ../macro.explain_fields.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct FieldsReport<'a> {
+    /// The type as written.
+    pub ty: &'static str,
+    /// The logical trait expression as written.
+    pub expr: &'static str,
+    /// Whether the type itself satisfies the expression.
+    pub value: bool,
+    /// Every field, in declaration order.
+    pub fields: &'a [Field],
+}
+
+impl<'a> FieldsReport<'a> {
+    /// Returns the fields whose types do not satisfy the expression.
+    pub fn offending(&self) -> impl Iterator<Item = &'a Field> {
+        self.fields.iter().filter(|field| !field.value)
+    }
+}
+
+impl<'a> fmt::Display for FieldsReport<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} = {}", self.ty, self.expr, self.value)?;
+        for field in self.fields {
+            write!(f, "\n  {}: {} = {}", field, field.ty, field.value)?;
+            if !field.value {
+                f.write_str(" (offending)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a [`&'static FieldsReport`] of a logical trait expression evaluated
+/// over a type and the type of each of its fields.
+///
+/// The type must derive [`ImplsFields`], and this must be used after its
+/// definition in the same module or a child module. Fields of every enum
+/// variant are included. Generic arguments written here are substituted into
+/// field types.
+///
+/// Because the report is a constant, the type must not depend on generic
+/// parameters.
+///
+/// This requires the `macros` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use impls::ImplsFields;
+/// use std::rc::Rc;
+///
+/// #[derive(ImplsFields)]
+/// enum Conn {
+///     Tcp { addr: String, buf: *const u8 },
+///     Local(Rc<str>),
+/// }
+///
+/// # fn main() {
+/// let report = explain_fields!(Conn: Send & Sync);
+/// assert!(!report.value);
+/// assert_eq!(report.to_string(), "\
+/// Conn: Send & Sync = false
+///   Tcp.addr: String = true
+///   Tcp.buf: *const u8 = false (offending)
+///   Local.0: Rc<str> = false (offending)");
+/// # }
+/// ```
+///
+/// [`&'static FieldsReport`]: fields/struct.FieldsReport.html
+/// [`ImplsFields`]: derive.ImplsFields.html
+#[macro_export(local_inner_macros)]
+macro_rules! explain_fields {
+    ($type:ty: $($trait_expr:tt)+) => {
+        _impls_fields!([$crate::_explain_fields] [$($trait_expr)+] $type)
+    };
+}
+
+/// Builds the output of `explain_fields` from the fields of a type.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _explain_fields {
+    ($trait_expr:tt [$type:ty] $kind:tt $fields:tt) => {{
+        const REPORT: &$crate::fields::FieldsReport<'static> =
+            &$crate::fields::FieldsReport {
+                ty: _impls_stringify!($type),
+                expr: _explain_fields!(@stringify $trait_expr),
+                value: _explain_fields!(@value $type: $trait_expr),
+                fields: _explain_fields!(@fields $trait_expr $fields),
+            };
+        REPORT
+    }};
+    (@stringify [$($trait_expr:tt)+]) => {
+        _impls_stringify!($($trait_expr)+)
+    };
+    (@value $type:ty: [$($trait_expr:tt)+]) => {
+        _impls!(@bool $type: $($trait_expr)+)
+    };
+    (
+        @field [$($trait_expr:tt)+] $variant:tt $name:expr, $ty_name:expr =>
+        $field:ty
+    ) => {
+        $crate::fields::Field {
+            variant: _explain_fields!(@variant $variant),
+            name: $name,
+            ty: $ty_name,
+            value: _explain_fields!(@value $field: [$($trait_expr)+]),
+        }
+    };
+    (
+        @fields $trait_expr:tt
+        [$([$variant:tt $name:expr, $ty_name:expr => $field:ty])*]
+    ) => {
+        &[$(_explain_fields!(
+            @field $trait_expr $variant $name, $ty_name => $field
+        )),*]
+    };
+    (@variant []) => {
+        $crate::_core::option::Option::None
+    };
+    (@variant [$variant:expr]) => {
+        $crate::_core::option::Option::Some($variant)
+    };
+}
+
+/// An auto trait of a type, compared with whether every field implements it.
+#[derive(Clone, Copy, Debug)]
+pub struct AutoTrait<'a> {
+    /// The trait's name.
+    pub name: &'static str,
+    /// Whether the type implements the trait.
+    pub actual: bool,
+    /// Every field, and whether its type implements the trait.
+    pub fields: &'a [Field],
+}
+
+impl<'a> AutoTrait<'a> {
+    /// Returns `true` if every field implements the trait, which is when the
+    /// compiler implements it automatically.
+    pub fn structural(&self) -> bool {
+        self.fields.iter().all(|field| field.value)
+    }
+
+    /// Returns `true` if the type's implementation differs from its structure,
+    /// which requires a manual `impl` such as `unsafe impl Send`.
+    pub fn is_overridden(&self) -> bool {
+        self.actual != self.structural()
+    }
+
+    /// Returns the fields that do not implement the trait.
+    pub fn blocking(&self) -> impl Iterator<Item = &'a Field> {
+        self.fields.iter().filter(|field| !field.value)
+    }
+}
+
+/// The auto traits of a type, returned by [`audit_auto_traits!`].
+///
+/// Its [`Display`] implementation lists every auto trait, marks the ones that
+/// are overridden, and lists the fields that do not implement them:
+///
+/// ```text
+/// Pool:
+///   Send = true, structurally false (overridden)
+///     conn: *mut u8
+///   Sync = false
+///     conn: *mut u8
+///   Unpin = true
+/// ```
+///
+/// [`audit_auto_traits!`]: ../macro.audit_auto_traits.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct AutoTraits<'a> {
+    /// The type as written.
+    pub ty: &'static str,
+    /// Every auto trait checked.
+    pub traits: &'a [AutoTrait<'a>],
+}
+
+impl<'a> AutoTraits<'a> {
+    /// Returns the auto traits whose implementation differs from structure.
+    pub fn overridden(&self) -> impl Iterator<Item = &'a AutoTrait<'a>> {
+        self.traits.iter().filter(|t| t.is_overridden())
+    }
+
+    /// Returns the auto trait named `name`, if it was checked.
+    pub fn get(&self, name: &str) -> Option<&'a AutoTrait<'a>> {
+        self.traits.iter().find(|t| t.name == name)
+    }
+}
+
+impl<'a> fmt::Display for AutoTraits<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.ty)?;
+        for t in self.traits {
+            write!(f, "\n  {} = {}", t.name, t.actual)?;
+            if t.is_overridden() {
+                write!(f, ", structurally {} (overridden)", t.structural())?;
+            }
+            for field in t.blocking() {
+                write!(f, "\n    {}: {}", field, field.ty)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns [`&'static AutoTraits`] that compare each auto trait of a type with
+/// whether all of its fields implement it.
+///
+/// The compiler implements an auto trait for a type exactly when every field
+/// implements it, unless that is overridden by a manual `impl`. This flags
+/// such overrides, such as an `unsafe impl Send` written for fields that have
+/// since changed, so that they can be reviewed.
+///
+/// The checked traits are `Send`, `Sync`, and `Unpin`, as well as
+/// `UnwindSafe` and `RefUnwindSafe` if the `std` feature is enabled.
+///
+/// The type must derive [`ImplsFields`], and the same restrictions as
+/// [`explain_fields!`] apply. This requires the `macros` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use impls::ImplsFields;
+///
+/// #[derive(ImplsFields)]
+/// struct Pool {
+///     conn: *mut u8,
+///     size: usize,
+/// }
+///
+/// // SAFETY: `conn` is only accessed through `&mut Pool`.
+/// unsafe impl Send for Pool {}
+///
+/// # fn main() {
+/// let audit = audit_auto_traits!(Pool);
+/// let overridden: Vec<_> = audit.overridden().map(|t| t.name).collect();
+/// assert_eq!(overridden, ["Send"]);
+///
+/// let send = audit.get("Send").unwrap();
+/// let blocking: Vec<_> = send.blocking().map(|f| f.name).collect();
+/// assert_eq!(blocking, ["conn"]);
+/// # }
+/// ```
+///
+/// [`&'static AutoTraits`]: fields/struct.AutoTraits.html
+/// [`ImplsFields`]: derive.ImplsFields.html
+/// [`explain_fields!`]: macro.explain_fields.html
+#[macro_export(local_inner_macros)]
+macro_rules! audit_auto_traits {
+    ($type:ty) => {
+        _impls_fields!([$crate::_audit_auto_traits] [] $type)
+    };
+}
+
+/// Builds the output of `audit_auto_traits` from the fields of a type.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _audit_auto_traits {
+    ([] $type:tt $kind:tt $fields:tt) => {
+        _impls_auto_traits!(_audit_auto_traits [@build $type $fields])
+    };
+    (@build [$type:ty] $fields:tt [$([$name:expr => $trait:tt])+]) => {{
+        const AUTO_TRAITS: &$crate::fields::AutoTraits<'static> =
+            &$crate::fields::AutoTraits {
+                ty: _impls_stringify!($type),
+                traits: &[$($crate::fields::AutoTrait {
+                    name: $name,
+                    actual: _explain_fields!(@value $type: $trait),
+                    fields: _explain_fields!(@fields $trait $fields),
+                }),+],
+            };
+        AUTO_TRAITS
+    }};
+}
+
+/// Passes the auto traits to check to the `$cb` macro after `$args`, including
+/// unwind safety traits if the `std` feature is enabled.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_auto_traits {
+    ($cb:ident [$($args:tt)*]) => {
+        $cb!($($args)* [
+            ["Send" => [$crate::_core::marker::Send]]
+            ["Sync" => [$crate::_core::marker::Sync]]
+            ["Unpin" => [$crate::_core::marker::Unpin]]
+            ["UnwindSafe" => [$crate::_std::panic::UnwindSafe]]
+            ["RefUnwindSafe" => [$crate::_std::panic::RefUnwindSafe]]
+        ])
+    };
+}
+
+/// Passes the auto traits to check to the `$cb` macro after `$args`, including
+/// unwind safety traits if the `std` feature is enabled.
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_auto_traits {
+    ($cb:ident [$($args:tt)*]) => {
+        $cb!($($args)* [
+            ["Send" => [$crate::_core::marker::Send]]
+            ["Sync" => [$crate::_core::marker::Sync]]
+            ["Unpin" => [$crate::_core::marker::Unpin]]
+        ])
+    };
+}
+
+/// A standard derive and whether it would succeed for a type.
+#[derive(Clone, Copy, Debug)]
+pub struct Derive<'a> {
+    /// The derive's name, such as `Clone`.
+    pub name: &'static str,
+    /// A requirement of the type itself that is not met, such as a unit
+    /// variant for `Default` on enums.
+    pub unmet: Option<&'static str>,
+    /// The fields that the derive requires to implement the trait, and whether
+    /// each one does.
+    pub fields: &'a [Field],
+}
+
+impl<'a> Derive<'a> {
+    /// Returns `true` if the derive would succeed.
+    pub fn is_achievable(&self) -> bool {
+        self.unmet.is_none() && self.fields.iter().all(|field| field.value)
+    }
+
+    /// Returns the fields that do not implement the trait.
+    pub fn blocking(&self) -> impl Iterator<Item = &'a Field> {
+        self.fields.iter().filter(|field| !field.value)
+    }
+}
+
+/// Which standard derives would succeed for a type, returned by
+/// [`derive_eligibility!`].
+///
+/// Its [`Display`] implementation lists every derive, followed by what blocks
+/// it:
+///
+/// ```text
+/// Config:
+///   Clone = true
+///   Copy = false
+///     name: String
+///   Default = false (requires a unit variant marked `#[default]`)
+/// ```
+///
+/// [`derive_eligibility!`]: ../macro.derive_eligibility.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct Eligibility<'a> {
+    /// The type as written.
+    pub ty: &'static str,
+    /// Every derive checked, in the order `Clone`, `Copy`, `Debug`,
+    /// `PartialEq`, `Eq`, `Hash`, `Default`, `PartialOrd`, and `Ord`.
+    pub derives: &'a [Derive<'a>],
+}
+
+impl<'a> Eligibility<'a> {
+    /// Returns the names of the derives that would succeed.
+    pub fn achievable(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.derives
+            .iter()
+            .filter(|d| d.is_achievable())
+            .map(|d| d.name)
+    }
+
+    /// Returns the derives that would fail.
+    pub fn blocked(&self) -> impl Iterator<Item = &'a Derive<'a>> {
+        self.derives.iter().filter(|d| !d.is_achievable())
+    }
+
+    /// Returns the derive named `name`, if it was checked.
+    pub fn get(&self, name: &str) -> Option<&'a Derive<'a>> {
+        self.derives.iter().find(|d| d.name == name)
+    }
+}
+
+impl<'a> fmt::Display for Eligibility<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.ty)?;
+        for derive in self.derives {
+            write!(f, "\n  {} = {}", derive.name, derive.is_achievable())?;
+            if let Some(unmet) = derive.unmet {
+                write!(f, " (requires {})", unmet)?;
+            }
+            for field in derive.blocking() {
+                write!(f, "\n    {}: {}", field, field.ty)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns an [`&'static Eligibility`] of which standard derives would succeed
+/// for a type, and which fields block the others.
+///
+/// The checked derives are `Clone`, `Copy`, `Debug`, `PartialEq`, `Eq`,
+/// `Hash`, `Default`, `PartialOrd`, and `Ord`. Each requires every field to
+/// implement its trait, with these exceptions:
+/// - `Default` on enums requires a unit variant to mark as `#[default]`.
+/// - Unions can only derive `Clone` and `Copy`, which require every field to
+///   be `Copy`.
+///
+/// The type must derive [`ImplsFields`], and the same restrictions as
+/// [`explain_fields!`] apply. This requires the `macros` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use impls::ImplsFields;
+///
+/// #[derive(ImplsFields)]
+/// struct Config {
+///     name: String,
+///     retries: u8,
+///     ratio: f32,
+/// }
+///
+/// # fn main() {
+/// let eligibility = derive_eligibility!(Config);
+/// let achievable: Vec<_> = eligibility.achievable().collect();
+/// assert_eq!(
+///     achievable,
+///     ["Clone", "Debug", "PartialEq", "Default", "PartialOrd"],
+/// );
+///
+/// let ord = eligibility.get("Ord").unwrap();
+/// let blocking: Vec<_> = ord.blocking().map(|f| f.name).collect();
+/// assert_eq!(blocking, ["ratio"]);
+/// # }
+/// ```
+///
+/// [`&'static Eligibility`]: fields/struct.Eligibility.html
+/// [`ImplsFields`]: derive.ImplsFields.html
+/// [`explain_fields!`]: macro.explain_fields.html
+#[macro_export(local_inner_macros)]
+macro_rules! derive_eligibility {
+    ($type:ty) => {
+        _impls_fields!([$crate::_derive_eligibility] [] $type)
+    };
+}
+
+/// Builds the output of `derive_eligibility` from the fields of a type.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _derive_eligibility {
+    ([] [$type:ty] $kind:tt $fields:tt) => {{
+        const ELIGIBILITY: &$crate::fields::Eligibility<'static> =
+            &$crate::fields::Eligibility {
+                ty: _impls_stringify!($type),
+                derives: &[
+                    _derive_eligibility!(@derive $kind $fields Clone
+                        [$crate::_core::clone::Clone]),
+                    _derive_eligibility!(@derive $kind $fields Copy
+                        [$crate::_core::marker::Copy]),
+                    _derive_eligibility!(@derive $kind $fields Debug
+                        [$crate::_core::fmt::Debug]),
+                    _derive_eligibility!(@derive $kind $fields PartialEq
+                        [$crate::_core::cmp::PartialEq]),
+                    _derive_eligibility!(@derive $kind $fields Eq
+                        [$crate::_core::cmp::Eq]),
+                    _derive_eligibility!(@derive $kind $fields Hash
+                        [$crate::_core::hash::Hash]),
+                    _derive_eligibility!(@derive $kind $fields Default
+                        [$crate::_core::default::Default]),
+                    _derive_eligibility!(@derive $kind $fields PartialOrd
+                        [$crate::_core::cmp::PartialOrd]),
+                    _derive_eligibility!(@derive $kind $fields Ord
+                        [$crate::_core::cmp::Ord]),
+                ],
+            };
+        ELIGIBILITY
+    }};
+    (@derive [union] $fields:tt Clone $trait:tt) => {
+        _derive_eligibility!(
+            @fields Clone [$crate::_core::marker::Copy] $fields
+        )
+    };
+    (@derive [union] $fields:tt Copy $trait:tt) => {
+        _derive_eligibility!(@fields Copy $trait $fields)
+    };
+    (@derive [union] $fields:tt $name:ident $trait:tt) => {
+        _derive_eligibility!(@unmet $name "a struct or enum")
+    };
+    (@derive [enum] $fields:tt Default $trait:tt) => {
+        _derive_eligibility!(
+            @unmet Default "a unit variant marked `#[default]`"
+        )
+    };
+    (@derive [enum $($unit:tt)+] $fields:tt Default $trait:tt) => {
+        $crate::fields::Derive {
+            name: "Default",
+            unmet: $crate::_core::option::Option::None,
+            fields: &[],
+        }
+    };
+    (@derive $kind:tt $fields:tt $name:ident $trait:tt) => {
+        _derive_eligibility!(@fields $name $trait $fields)
+    };
+    (@fields $name:ident $trait:tt $fields:tt) => {
+        $crate::fields::Derive {
+            name: _impls_stringify!($name),
+            unmet: $crate::_core::option::Option::None,
+            fields: _explain_fields!(@fields $trait $fields),
+        }
+    };
+    (@unmet $name:ident $unmet:expr) => {
+        $crate::fields::Derive {
+            name: _impls_stringify!($name),
+            unmet: $crate::_core::option::Option::Some($unmet),
+            fields: &[],
+        }
+    };
+}
@@ -0,0 +1,270 @@
+//! Whether a logical trait expression propagates through pointer and
+//! container forms of a type.
+//!
+//! Use [`impls_forms!`] to create a [`Forms`].
+//!
+//! [`impls_forms!`]: ../macro.impls_forms.html
+//! [`Forms`]: struct.Forms.html
+
+use matrix::Matrix;
+
+/// A logical trait expression evaluated over a type and its pointer and
+/// container forms, returned by [`impls_forms!`].
+///
+/// [`impls_forms!`]: ../macro.impls_forms.html
+#[derive(Clone, Copy, Debug)]
+pub struct Forms<'a> {
+    /// Whether each form implements the expression, in a single column.
+    ///
+    /// The first row is the type itself, followed by each form, such as
+    /// `&T` or `Box<T>`.
+    pub matrix: Matrix<'a>,
+}
+
+impl<'a> Forms<'a> {
+    /// Returns the type as written.
+    pub fn ty(&self) -> &'static str {
+        self.matrix.rows[0]
+    }
+
+    /// Returns the logical trait expression as written.
+    pub fn expr(&self) -> &'static str {
+        self.matrix.columns[0]
+    }
+
+    /// Returns whether the type itself implements the expression.
+    pub fn implements(&self) -> bool {
+        self.matrix.cells[0][0]
+    }
+
+    /// Returns whether `form` implements the expression, or `None` if it was
+    /// not evaluated.
+    ///
+    /// Forms are written with the type filled in, such as `Box<MyType>`.
+    pub fn get(&self, form: &str) -> Option<bool> {
+        let row = self.matrix.rows.iter().position(|&row| row == form)?;
+        self.matrix.get(row, 0)
+    }
+
+    /// Returns an iterator over each form and whether it implements the
+    /// expression, excluding the type itself.
+    pub fn forms(&self) -> impl Iterator<Item = (&'static str, bool)> + 'a {
+        let rows = self.matrix.rows.iter().skip(1);
+        rows.zip(&self.matrix.cells[1..])
+            .map(|(&form, cells)| (form, cells[0]))
+    }
+
+    /// Returns an iterator over the forms that do not implement the
+    /// expression.
+    pub fn missing(&self) -> impl Iterator<Item = &'static str> + 'a {
+        self.forms()
+            .filter(|&(_, implements)| !implements)
+            .map(|(form, _)| form)
+    }
+}
+
+/// Returns a [`&'static Forms`] of whether a type's pointer and container
+/// forms implement the same logical trait expression as the type.
+///
+/// This catches missing forwarding impls, such as a trait implemented for `T`
+/// but not for `&T` or `Box<T>`. The forms are:
+///
+/// - `&T` and `&mut T`.
+/// - `Option<T>`, `[T; 1]`, and `[T]`.
+/// - `Box<T>`, `Rc<T>`, `Arc<T>`, and `Vec<T>`, if the `alloc` feature is
+///   enabled.
+///
+/// Unsized types, written as `dyn Trait`, `str`, or `[T]`, only have the
+/// pointer forms `&T`, `&mut T`, `Box<T>`, `Rc<T>`, and `Arc<T>`. Other
+/// unsized types, such as `Path` or `OsStr`, are written with a leading
+/// `?Sized`, as in `impls_forms!(?Sized Path: Debug)`.
+///
+/// The result can be rendered as a table via its [`matrix`].
+///
+/// Because the result is a constant, the type must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// trait Describe {
+///     fn describe(&self) -> String;
+/// }
+///
+/// struct Point(i32, i32);
+///
+/// impl Describe for Point {
+///     fn describe(&self) -> String {
+///         format!("({}, {})", self.0, self.1)
+///     }
+/// }
+///
+/// impl<'a, T: Describe + ?Sized> Describe for &'a T {
+///     fn describe(&self) -> String {
+///         (**self).describe()
+///     }
+/// }
+///
+/// let forms = impls_forms!(Point: Describe);
+///
+/// assert!(forms.implements());
+/// assert_eq!(forms.get("&Point"), Some(true));
+/// assert_eq!(forms.get("&mut Point"), Some(false));
+///
+/// let forms = impls_forms!(dyn Describe: Describe);
+///
+/// assert_eq!(forms.get("&dyn Describe"), Some(true));
+/// assert_eq!(forms.get("Option<dyn Describe>"), None);
+///
+/// let forms = impls_forms!(?Sized std::path::Path: std::fmt::Debug);
+///
+/// assert_eq!(forms.get("&std::path::Path"), Some(true));
+/// assert_eq!(forms.get("[std::path::Path]"), None);
+/// ```
+///
+/// With the `alloc` feature, `forms.matrix.text()` of `Point` is printed as:
+///
+/// ```text
+///               | Describe
+/// --------------+---------
+/// Point         | yes
+/// &Point        | yes
+/// &mut Point    | no
+/// Option<Point> | no
+/// [Point; 1]    | no
+/// [Point]       | no
+/// Box<Point>    | no
+/// Rc<Point>     | no
+/// Arc<Point>    | no
+/// Vec<Point>    | no
+/// ```
+///
+/// [`&'static Forms`]: forms/struct.Forms.html
+/// [`matrix`]: forms/struct.Forms.html#structfield.matrix
+#[macro_export(local_inner_macros)]
+macro_rules! impls_forms {
+    (dyn $($input:tt)+) => {
+        _impls_forms!(@dyn [] [] dyn $($input)+)
+    };
+    (str: $($trait_expr:tt)+) => {
+        _impls_forms!(@unsized ["&", "", "&mut ", ""] str: [$($trait_expr)+])
+    };
+    ([$elem:ty]: $($trait_expr:tt)+) => {
+        _impls_forms!(
+            @unsized ["&", "", "&mut ", ""] [$elem]: [$($trait_expr)+]
+        )
+    };
+    (?Sized $type:ty: $($trait_expr:tt)+) => {
+        _impls_forms!(
+            @unsized ["&", "", "&mut ", ""] $type: [$($trait_expr)+]
+        )
+    };
+    ($type:ty: $($trait_expr:tt)+) => {
+        _impls_forms!(@sized $type: [$($trait_expr)+])
+    };
+}
+
+/// Builds the output of `impls_forms`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_forms {
+    // Collect the trait object type up to the `:`. References to trait
+    // objects with several bounds are labeled with parentheses, as in
+    // `&(dyn A + B)`.
+    (@dyn $paren:tt [$($type:tt)*] : $($trait_expr:tt)+) => {
+        _impls_forms!(@dyn_type $paren [$($type)*] [$($trait_expr)+])
+    };
+    (@dyn $paren:tt [$($type:tt)*] + $($rest:tt)+) => {
+        _impls_forms!(@dyn [+] [$($type)* +] $($rest)+)
+    };
+    (@dyn $paren:tt [$($type:tt)*] $next:tt $($rest:tt)+) => {
+        _impls_forms!(@dyn $paren [$($type)* $next] $($rest)+)
+    };
+    (@dyn_type [] [$type:ty] $trait_expr:tt) => {
+        _impls_forms!(@unsized ["&", "", "&mut ", ""] $type: $trait_expr)
+    };
+    (@dyn_type [+] [$type:ty] $trait_expr:tt) => {
+        _impls_forms!(@unsized ["&(", ")", "&mut (", ")"] $type: $trait_expr)
+    };
+
+    (
+        @unsized [$ref:expr, $ref_end:expr, $mut:expr, $mut_end:expr]
+        $type:ty: $trait_expr:tt
+    ) => {
+        _impls_forms_alloc!(@unsized $type: $trait_expr [
+            [_impls_forms!(@label $ref, $type, $ref_end) => &'static $type]
+            [_impls_forms!(@label $mut, $type, $mut_end) => &'static mut $type]
+        ])
+    };
+    (@sized $type:ty: $trait_expr:tt) => {
+        _impls_forms_alloc!(@sized $type: $trait_expr [
+            [_impls_forms!(@label "&", $type, "") => &'static $type]
+            [_impls_forms!(@label "&mut ", $type, "") => &'static mut $type]
+            [_impls_forms!(@label "Option<", $type, ">") =>
+                $crate::_core::option::Option<$type>]
+            [_impls_forms!(@label "[", $type, "; 1]") => [$type; 1]]
+            [_impls_forms!(@label "[", $type, "]") => [$type]]
+        ])
+    };
+    (@label $start:expr, $type:ty, $end:expr) => {
+        _impls_concat!($start, _impls_stringify!($type), $end)
+    };
+
+    (@output $type:ty: $trait_expr:tt [$([$label:expr => $form:ty])+]) => {{
+        const FORMS: &$crate::forms::Forms<'static> = &$crate::forms::Forms {
+            matrix: $crate::matrix::Matrix {
+                rows: &[_impls_stringify!($type), $($label),+],
+                columns: &[_impls_forms!(@column $trait_expr)],
+                cells: &[
+                    &[_impls_forms!(@bool $type: $trait_expr)],
+                    $(&[_impls_forms!(@bool $form: $trait_expr)]),+
+                ],
+            },
+        };
+        FORMS
+    }};
+    (@column [$($trait_expr:tt)+]) => {
+        _impls_stringify!($($trait_expr)+)
+    };
+    (@bool $type:ty: [$($trait_expr:tt)+]) => {
+        _impls!(@bool $type: $($trait_expr)+)
+    };
+}
+
+/// Appends forms from `alloc` if the `alloc` feature is enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_forms_alloc {
+    (@unsized $type:ty: $trait_expr:tt $core:tt) => {
+        _impls_forms_alloc!(@pointers $type: $trait_expr $core [])
+    };
+    (@sized $type:ty: $trait_expr:tt $core:tt) => {
+        _impls_forms_alloc!(@pointers $type: $trait_expr $core [
+            [_impls_forms!(@label "Vec<", $type, ">") =>
+                $crate::_alloc::vec::Vec<$type>]
+        ])
+    };
+    (@pointers $type:ty: $trait_expr:tt [$($core:tt)+] [$($sized:tt)*]) => {
+        _impls_forms!(@output $type: $trait_expr [$($core)+
+            [_impls_forms!(@label "Box<", $type, ">") =>
+                $crate::_alloc::boxed::Box<$type>]
+            [_impls_forms!(@label "Rc<", $type, ">") =>
+                $crate::_alloc::rc::Rc<$type>]
+            [_impls_forms!(@label "Arc<", $type, ">") =>
+                $crate::_alloc::sync::Arc<$type>]
+            $($sized)*
+        ])
+    };
+}
+
+/// Appends forms from `alloc` if the `alloc` feature is enabled.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_forms_alloc {
+    (@$size:ident $type:ty: $trait_expr:tt $core:tt) => {
+        _impls_forms!(@output $type: $trait_expr $core)
+    };
+}
@@ -0,0 +1,424 @@
+//! Inference of the bounds that generic parameters need for a type to
+//! implement a logical trait expression.
+//!
+//! Use [`infer_bounds!`] to create [`Bounds`].
+//!
+//! [`infer_bounds!`]: ../macro.infer_bounds.html
+//! [`Bounds`]: struct.Bounds.html
+
+use _core::fmt;
+
+/// The bounds that each generic parameter of a type needs for the type to
+/// implement a logical trait expression, returned by [`infer_bounds!`].
+///
+/// Its [`Display`] implementation lists the minimal sets of candidate traits
+/// for each parameter:
+///
+/// ```text
+/// Channel<_, _>: Send & Sync
+///   parameter 1: {Send}
+///   parameter 2: {Send, Sync}
+/// ```
+///
+/// [`infer_bounds!`]: ../macro.infer_bounds.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds<'a> {
+    /// The type as written, with `_` for each parameter.
+    pub ty: &'static str,
+    /// The logical trait expression as written.
+    pub expr: &'static str,
+    /// The candidate traits as written.
+    pub candidates: &'a [&'static str],
+    /// The results for each parameter, in order of appearance.
+    pub params: &'a [Param<'a>],
+}
+
+/// Whether a type implements an expression for every set of candidate traits
+/// that one of its generic parameters may implement.
+///
+/// Sets are bit masks where bit `i` is set if the parameter implements the
+/// candidate at index `i`. The other parameters implement every candidate.
+#[derive(Clone, Copy, Debug)]
+pub struct Param<'a> {
+    /// Whether the type implements the expression, indexed by set.
+    pub values: &'a [bool],
+}
+
+impl<'a> Param<'a> {
+    /// Returns whether the type implements the expression when the parameter
+    /// implements exactly the candidates in `set`.
+    pub fn is_satisfied_by(&self, set: u64) -> bool {
+        self.values.get(set as usize).cloned().unwrap_or(false)
+    }
+
+    /// Returns `true` if `set` is satisfying and no proper subset of it is.
+    pub fn is_minimal(&self, set: u64) -> bool {
+        if !self.is_satisfied_by(set) {
+            return false;
+        }
+        let mut subset = set;
+        while subset != 0 {
+            subset = (subset - 1) & set;
+            if self.is_satisfied_by(subset) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<'a> Bounds<'a> {
+    /// Returns the minimal satisfying sets of candidates for the parameter at
+    /// `index`, or `None` if it is out of bounds.
+    ///
+    /// This is empty if no set of candidates is enough.
+    pub fn minimal(&self, index: usize) -> Option<MinimalSets<'a>> {
+        Some(MinimalSets {
+            candidates: self.candidates,
+            param: *self.params.get(index)?,
+            next: 0,
+        })
+    }
+}
+
+impl<'a> fmt::Display for Bounds<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.ty, self.expr)?;
+        for index in 0..self.params.len() {
+            write!(f, "\n  parameter {}: ", index + 1)?;
+
+            let mut sets = self.minimal(index).into_iter().flatten();
+            match sets.next() {
+                Some(first) => {
+                    write!(f, "{}", first)?;
+                    for set in sets {
+                        write!(f, " or {}", set)?;
+                    }
+                }
+                None => f.write_str("unsatisfiable")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the minimal satisfying sets of candidates for a parameter,
+/// returned by [`Bounds::minimal`](struct.Bounds.html#method.minimal).
+#[derive(Clone, Debug)]
+pub struct MinimalSets<'a> {
+    candidates: &'a [&'static str],
+    param: Param<'a>,
+    next: u64,
+}
+
+impl<'a> Iterator for MinimalSets<'a> {
+    type Item = BoundSet<'a>;
+
+    fn next(&mut self) -> Option<BoundSet<'a>> {
+        while (self.next as usize) < self.param.values.len() {
+            let set = self.next;
+            self.next += 1;
+            if self.param.is_minimal(set) {
+                return Some(BoundSet {
+                    candidates: self.candidates,
+                    set,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// A set of candidate traits, rendered as `{Send, Sync}`.
+#[derive(Clone, Copy, Debug)]
+pub struct BoundSet<'a> {
+    candidates: &'a [&'static str],
+    set: u64,
+}
+
+impl<'a> BoundSet<'a> {
+    /// Returns the bit mask of the candidates in this set.
+    pub fn mask(&self) -> u64 {
+        self.set
+    }
+
+    /// Calls `f` on the name of each candidate in this set.
+    pub fn for_each<F: FnMut(&'static str)>(&self, mut f: F) {
+        for (i, &name) in self.candidates.iter().enumerate() {
+            if self.set.checked_shr(i as u32).unwrap_or(0) & 1 != 0 {
+                f(name);
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for BoundSet<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut result = f.write_str("{");
+        let mut first = true;
+        self.for_each(|name| {
+            if !first {
+                result = result.and_then(|_| f.write_str(", "));
+            }
+            result = result.and_then(|_| f.write_str(name));
+            first = false;
+        });
+        result.and_then(|_| f.write_str("}"))
+    }
+}
+
+/// Returns the [`&'static Bounds`] that each `_` parameter of a type needs for
+/// the type to implement a logical trait expression.
+///
+/// Each parameter is instantiated with a witness type for every subset of the
+/// candidate traits, as with [`impls!(for<...> ...)`][witness], while the other
+/// parameters implement every candidate. The minimal subsets that satisfy the
+/// expression are the inferred bounds.
+///
+/// This evaluates the expression once per subset of candidates for each
+/// parameter, so the number of candidates should be kept small. Candidates are
+/// implemented the same way as for witness types, except that subtraits such
+/// as `Copy` are only implemented in subsets with their supertraits.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::sync::{Arc, Mutex};
+///
+/// struct Channel<T, A> {
+///     queue: Mutex<Vec<T>>,
+///     alloc: Arc<A>,
+/// }
+///
+/// let bounds = infer_bounds!(
+///     Channel<_, _>: Send & Sync over {Send, Sync, Clone}
+/// );
+///
+/// assert_eq!(bounds.to_string(), "\
+/// Channel<_, _>: Send & Sync
+///   parameter 1: {Send}
+///   parameter 2: {Send, Sync}");
+/// ```
+///
+/// A parameter can have several minimal sets, or none:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+///
+/// let bounds = infer_bounds!(Option<_>: Send | Clone over {Send, Clone});
+/// assert_eq!(bounds.minimal(0).unwrap().count(), 2);
+///
+/// let bounds = infer_bounds!(Rc<_>: Send over {Send, Sync});
+/// assert_eq!(bounds.minimal(0).unwrap().count(), 0);
+/// ```
+///
+/// [`&'static Bounds`]: infer/struct.Bounds.html
+/// [witness]: macro.impls.html#generic-types
+#[macro_export(local_inner_macros)]
+macro_rules! infer_bounds {
+    ($($input:tt)+) => {
+        _infer_bounds!(@type [] $($input)+)
+    };
+}
+
+/// Builds the output of `infer_bounds`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _infer_bounds {
+    // Split the input into the type, the expression, and the candidates.
+    (@type [$($type:tt)+] : $($rest:tt)+) => {
+        _infer_bounds!(@expr [$($type)+] [] $($rest)+)
+    };
+    (@type [$($type:tt)*] $next:tt $($rest:tt)+) => {
+        _infer_bounds!(@type [$($type)* $next] $($rest)+)
+    };
+    (@expr $type:tt [$($trait_expr:tt)+] over {$($candidates:tt)+}) => {
+        _impls_split!(
+            _infer_bounds [@candidates $type [$($trait_expr)+]]
+            $($candidates)+
+        )
+    };
+    (@expr $type:tt [$($trait_expr:tt)*] $next:tt $($rest:tt)+) => {
+        _infer_bounds!(@expr $type [$($trait_expr)* $next] $($rest)+)
+    };
+
+    (@candidates $type:tt $trait_expr:tt [$(($($candidate:tt)+))+]) => {
+        _infer_bounds!(
+            @subsets $type $trait_expr [$([$($candidate)+])+]
+            [[]] [$([$($candidate)+])+]
+        )
+    };
+
+    // Build every subset of the candidates, ordered by bit mask.
+    (
+        @subsets $type:tt $trait_expr:tt $all:tt
+        [$([$($subset:tt)*])+] [$next:tt $($rest:tt)*]
+    ) => {
+        _infer_bounds!(
+            @subsets $type $trait_expr $all
+            [$([$($subset)*])+ $([$($subset)* $next])+] [$($rest)*]
+        )
+    };
+    (@subsets $type:tt $trait_expr:tt $all:tt $subsets:tt []) => {
+        _infer_bounds!(@count $trait_expr $all $subsets $type [] [] $type)
+    };
+
+    // Count each `_`, including within tuples and arrays, to give each one
+    // the number of `_` before it.
+    (
+        @count $trait_expr:tt $all:tt $subsets:tt $type:tt
+        [$($counts:tt)*] [$($count:tt)*] [_ $($rest:tt)*]
+    ) => {
+        _infer_bounds!(
+            @count $trait_expr $all $subsets $type
+            [$($counts)* [$($count)*]] [$($count)* @] [$($rest)*]
+        )
+    };
+    (
+        @count $trait_expr:tt $all:tt $subsets:tt $type:tt $counts:tt $count:tt
+        [($($inner:tt)*) $($rest:tt)*]
+    ) => {
+        _infer_bounds!(
+            @count $trait_expr $all $subsets $type $counts $count
+            [$($inner)* $($rest)*]
+        )
+    };
+    (
+        @count $trait_expr:tt $all:tt $subsets:tt $type:tt $counts:tt $count:tt
+        [[$($inner:tt)*] $($rest:tt)*]
+    ) => {
+        _infer_bounds!(
+            @count $trait_expr $all $subsets $type $counts $count
+            [$($inner)* $($rest)*]
+        )
+    };
+    (
+        @count $trait_expr:tt $all:tt $subsets:tt $type:tt $counts:tt $count:tt
+        [$next:tt $($rest:tt)*]
+    ) => {
+        _infer_bounds!(
+            @count $trait_expr $all $subsets $type $counts $count [$($rest)*]
+        )
+    };
+    (
+        @count $trait_expr:tt $all:tt $subsets:tt $type:tt
+        [$($counts:tt)*] $count:tt []
+    ) => {{
+        const BOUNDS: &$crate::infer::Bounds<'static> =
+            &$crate::infer::Bounds {
+                ty: _infer_bounds!(@stringify_type $type),
+                expr: _infer_bounds!(@stringify $trait_expr),
+                candidates: _infer_bounds!(@names $all),
+                params: &[$(_infer_bounds!(
+                    @subst $trait_expr $all $subsets $counts [] $type
+                )),*],
+            };
+        BOUNDS
+    }};
+
+    // Copy the type, replacing the `_` after `$count` others with the varied
+    // witness and every other `_` with the witness of all candidates. Groups
+    // push the tokens before them and the tokens after them.
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        [] [$($ty:tt)*] [_ $($rest:tt)*] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            [-] [$($ty)* __ImplsVar] [$($rest)*] $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        [@ $($count:tt)*] [$($ty:tt)*] [_ $($rest:tt)*] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            [$($count)*] [$($ty)* __ImplsAll] [$($rest)*] $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        [-] [$($ty:tt)*] [_ $($rest:tt)*] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            [-] [$($ty)* __ImplsAll] [$($rest)*] $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        $count:tt $ty:tt [($($inner:tt)*) $($rest:tt)*] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            $count [] [$($inner)*] [() $ty [$($rest)*]] $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        $count:tt $ty:tt [[$($inner:tt)*] $($rest:tt)*] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            $count [] [$($inner)*] [[] $ty [$($rest)*]] $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        $count:tt [$($ty:tt)*] [$next:tt $($rest:tt)*] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            $count [$($ty)* $next] [$($rest)*] $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        $count:tt [$($inner:tt)*] [] [() [$($ty:tt)*] $rest:tt] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            $count [$($ty)* ($($inner)*)] $rest $($stack)*
+        )
+    };
+    (
+        @subst $trait_expr:tt $all:tt $subsets:tt
+        $count:tt [$($inner:tt)*] [] [[] [$($ty:tt)*] $rest:tt] $($stack:tt)*
+    ) => {
+        _infer_bounds!(
+            @subst $trait_expr $all $subsets
+            $count [$($ty)* [$($inner)*]] $rest $($stack)*
+        )
+    };
+    (@subst $trait_expr:tt $all:tt [$($subset:tt)+] $count:tt $ty:tt []) => {
+        $crate::infer::Param {
+            values: &[$(
+                _infer_bounds!(@value $trait_expr $all $subset $ty)
+            ),+],
+        }
+    };
+
+    (@stringify [$($tokens:tt)+]) => {
+        _impls_stringify!($($tokens)+)
+    };
+    // Types are parsed before being stringified, which spaces their tokens
+    // the same way on every compiler version.
+    (@stringify_type [$type:ty]) => {
+        _impls_stringify!($type)
+    };
+    (@names [$([$($candidate:tt)+])+]) => {
+        &[$(_impls_stringify!($($candidate)+)),+]
+    };
+    (
+        @value [$($trait_expr:tt)+] [$($all:tt)+] [$($subset:tt)*]
+        [$($type:tt)+]
+    ) => {{
+        _impls_witness!(@type lenient __ImplsVar $($subset)*);
+        _impls_witness!(@type lenient __ImplsAll $($all)+);
+        _impls!(@bool $($type)+: $($trait_expr)+)
+    }};
+}
@@ -73,6 +73,9 @@
 //! or in a unit test, you can ensure that no API-breaking changes are made
 //! without noticing until it's too late.
 //!
+//! With the `std` feature, [`impls_snapshot!`] records which traits your types
+//! implement in a file and fails when that changes.
+//!
 //! # Usage
 //!
 //! This crate is available [on crates.io][crate] and can be used by adding the
@@ -99,6 +102,30 @@
 //! use impls::impls;
 //! ```
 //!
+//! This crate is `#![no_std]`. The [trait catalog][catalog] includes traits
+//! from `alloc` and `std` when the `alloc` and `std` features are enabled:
+//!
+//! ```toml
+//! [dependencies]
+//! impls = { version = "1", features = ["std"] }
+//! ```
+//!
+//! The `type_name` feature includes type names in the placeholders of
+//! [`maybe_debug!`][maybe_debug] and [`maybe_display!`][maybe_display]. It
+//! requires Rust 1.38.
+//!
+//! The `macros` feature enables [`#[assert_impls]`][assert_impls], which checks
+//! a type definition at compile-time,
+//! [`#[derive(ImplsFields)]`][ImplsFields], which finds the fields that break a
+//! trait expression, and [`impls_sweep!`][impls_sweep], which finds the const
+//! values and arities at which a trait expression changes. It requires Rust
+//! 1.61 or newer for its dependencies, while the rest of the crate supports
+//! Rust 1.37.
+//!
+//! Logical trait expressions are labeled in reports and tables as written,
+//! via `stringify!`. Older compilers, such as Rust 1.37, separate their
+//! tokens with spaces, as in `Clone & ! Copy`.
+//!
 //! # Vocabulary
 //!
 //! This documentation uses jargon that may be new to inexperienced Rust users.
@@ -283,6 +310,25 @@
 //! assert_ne!(is_copy::<u32>(), impls!(u32: Copy));
 //! ```
 //!
+//! To check a generic type for every parameter that satisfies some bounds,
+//! declare the parameters with `for<...>`. Each parameter becomes a fresh
+//! witness type that implements exactly its listed bounds, including auto
+//! traits such as [`Send`] and [`Sync`]:
+//!
+//! ```
+//! # #[macro_use] extern crate impls;
+//! use std::rc::Rc;
+//!
+//! assert!(impls!(for<T: Clone + Send> Vec<T>: Clone & Send));
+//! assert!(impls!(for<T: Clone> Rc<T>: Clone & !Send));
+//! assert!(impls!(for<T> Box<T>: !Clone & Unpin));
+//! assert!(impls!(for<K, V: Clone> Option<(K, V)>: !Clone));
+//! ```
+//!
+//! Standard traits such as `Clone`, `Debug`, `Hash`, and `Ord` are implemented
+//! by name, and their supertraits must be listed too. Any other trait is
+//! implemented with an empty `impl`, so it must have no required items.
+//!
 //! [precedence]: https://doc.rust-lang.org/reference/expressions.html#expression-precedence
 //! [`static_assertions`]: https://docs.rs/static_assertions
 //!
@@ -325,6 +371,8 @@
 //! becomes 32 × 1, which is 32. This then becomes the length of the byte array
 //! in `Foo`.
 //!
+//! To pick a type rather than a size, use [`impls_type_if!`].
+//!
 //! # How It Works
 //!
 //! This abuses [inherent `impl`] priority to determine that a trait is
@@ -391,8 +439,16 @@
 //!
 //! [`Cargo.toml`]: https://doc.rust-lang.org/cargo/reference/manifest.html
 //! [`impls!`]: macro.impls.html
+//! [`impls_type_if!`]: macro.impls_type_if.html
 //! [2018]: https://blog.rust-lang.org/2018/12/06/Rust-1.31-and-rust-2018.html#rust-2018
 //! [crate]: https://crates.io/crates/impls
+//! [catalog]: catalog/index.html
+//! [assert_impls]: attr.assert_impls.html
+//! [ImplsFields]: derive.ImplsFields.html
+//! [impls_sweep]: macro.impls_sweep.html
+//! [maybe_debug]: macro.maybe_debug.html
+//! [maybe_display]: macro.maybe_display.html
+//! [`impls_snapshot!`]: macro.impls_snapshot.html
 //!
 //! [`BitAnd`]: https://doc.rust-lang.org/std/ops/trait.BitAnd.html
 //! [`BitOr`]:  https://doc.rust-lang.org/std/ops/trait.BitOr.html
@@ -415,10 +471,58 @@
 )]
 // Allocating types like `String` are used when testing.
 #![cfg_attr(not(test), no_std)]
+// Needed by tests of `impls_mask!` with 64 expressions.
+#![cfg_attr(test, recursion_limit = "512")]
 
 #[doc(hidden)]
 pub extern crate core as _core;
 
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc as _alloc;
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub extern crate std as _std;
+
+#[cfg(feature = "macros")]
+extern crate impls_macros;
+
+#[cfg(feature = "macros")]
+pub use impls_macros::{assert_impls, impls_sweep, ImplsFields};
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use impls_macros::_impls_fields;
+
+#[macro_use]
+pub mod catalog;
+#[macro_use]
+pub mod diff;
+pub mod explain;
+#[macro_use]
+pub mod infer;
+#[macro_use]
+pub mod logic;
+#[cfg(feature = "macros")]
+#[macro_use]
+pub mod fields;
+#[macro_use]
+pub mod forms;
+#[macro_use]
+pub mod matrix;
+#[macro_use]
+pub mod marker;
+#[macro_use]
+pub mod maybe;
+#[macro_use]
+pub mod pair;
+#[cfg(feature = "std")]
+#[macro_use]
+pub mod snapshot;
+#[cfg(feature = "macros")]
+pub mod sweep;
+
 /// Returns `true` if a type implements a logical trait expression.
 ///
 // IMPORTANT: Update crate level docs when updating these examples!
@@ -547,6 +651,25 @@ pub extern crate core as _core;
 /// assert_ne!(is_copy::<u32>(), impls!(u32: Copy));
 /// ```
 ///
+/// To check a generic type for every parameter that satisfies some bounds,
+/// declare the parameters with `for<...>`. Each parameter becomes a fresh
+/// witness type that implements exactly its listed bounds, including auto
+/// traits such as [`Send`] and [`Sync`]:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+///
+/// assert!(impls!(for<T: Clone + Send> Vec<T>: Clone & Send));
+/// assert!(impls!(for<T: Clone> Rc<T>: Clone & !Send));
+/// assert!(impls!(for<T> Box<T>: !Clone & Unpin));
+/// assert!(impls!(for<K, V: Clone> Option<(K, V)>: !Clone));
+/// ```
+///
+/// Standard traits such as `Clone`, `Debug`, `Hash`, and `Ord` are implemented
+/// by name, and their supertraits must be listed too. Any other trait is
+/// implemented with an empty `impl`, so it must have no required items.
+///
 /// [precedence]: https://doc.rust-lang.org/reference/expressions.html#expression-precedence
 /// [`static_assertions`]: https://docs.rs/static_assertions
 ///
@@ -589,6 +712,8 @@ pub extern crate core as _core;
 /// becomes 32 × 1, which is 32. This then becomes the length of the byte array
 /// in `Foo`.
 ///
+/// To pick a type rather than a size, use [`impls_type_if!`].
+///
 /// # How It Works
 ///
 /// This abuses [inherent `impl`] priority to determine that a trait is
@@ -619,228 +744,1798 @@ pub extern crate core as _core;
 ///
 /// [compile-time]: https://en.wikipedia.org/wiki/Compile_time
 /// [inherent `impl`]: https://doc.rust-lang.org/reference/items/implementations.html#inherent-implementations
+/// [`impls_type_if!`]: macro.impls_type_if.html
 ///
 /// [`&mut T`]: https://doc.rust-lang.org/std/primitive.reference.html
 /// [`bool`]:   https://doc.rust-lang.org/std/primitive.bool.html
 /// [`Clone`]:  https://doc.rust-lang.org/std/clone/trait.Clone.html
 /// [`Copy`]:   https://doc.rust-lang.org/std/marker/trait.Copy.html
+/// [`Send`]:   https://doc.rust-lang.org/std/marker/trait.Send.html
 /// [`Sized`]:  https://doc.rust-lang.org/std/marker/trait.Sized.html
+/// [`Sync`]:   https://doc.rust-lang.org/std/marker/trait.Sync.html
 /// [`usize`]:  https://doc.rust-lang.org/std/primitive.usize.html
 #[macro_export(local_inner_macros)]
 macro_rules! impls {
+    (for<$param:ident $($rest:tt)+) => {
+        _impls_witness!(@for [] [] $param $($rest)+)
+    };
     ($type:ty: $($trait_expr:tt)+) => {
-        _impls!($type: $($trait_expr)+)
+        _impls!(@bool $type: $($trait_expr)+)
     };
 }
 
-/// Handles the dirty work of `impls`.
-#[doc(hidden)]
+/// Asserts at runtime that a type implements a logical trait expression.
+///
+/// On failure, this panics at the call site with a report that lists every
+/// trait in the expression, whether it is implemented, and how the operators
+/// combined them. See [`explain::Report`](explain/struct.Report.html).
+///
+/// This is useful in unit tests, where a failure should point out exactly
+/// which trait implementation changed.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// assert_impls_rt!(String: Clone & !Copy & Send & Sync);
+/// ```
+///
+/// If `Copy` were expected of `String`, the following:
+///
+/// ```should_panic
+/// # #[macro_use] extern crate impls;
+/// assert_impls_rt!(String: Clone & Copy & Send);
+/// ```
+///
+/// would panic with:
+///
+/// ```text
+/// assertion failed: `String: Clone & Copy & Send`
+///   Clone = true
+///   Copy  = false
+///   Send  = true
+///   parsed: Clone & (Copy & Send)
+///     with: true & (false & true) = false
+/// ```
 #[macro_export(local_inner_macros)]
-macro_rules! _impls {
-    // ONE: Turn `$trait` into `true` or `false` based on whether `$type`
-    // implements it.
-    ($type:ty: $(! !)* $trait:path) => {{
-        // Do not import types in order to prevent trait name collisions.
-
-        /// Fallback trait with `False` for `IMPLS` if the type does not
-        /// implement the given trait.
-        trait DoesNotImpl {
-            const IMPLS: bool = false;
+macro_rules! assert_impls_rt {
+    ($type:ty: $($trait_expr:tt)+) => {
+        // Binding in a `match` keeps the tree's temporaries alive.
+        match _impls!(@node $type: $($trait_expr)+) {
+            ref root => if !root.value() {
+                $crate::_core::panic!(
+                    "{}",
+                    $crate::explain::Report::new(
+                        _impls_stringify!($type),
+                        _impls_stringify!($($trait_expr)+),
+                        root,
+                    )
+                );
+            }
         }
-        impl<T: ?Sized> DoesNotImpl for T {}
-
-        /// Concrete type with `True` for `IMPLS` if the type implements the
-        /// given trait. Otherwise, it falls back to `DoesNotImpl`.
-        struct Wrapper<T: ?Sized>($crate::_core::marker::PhantomData<T>);
+    };
+}
 
-        #[allow(dead_code)]
-        impl<T: ?Sized + $trait> Wrapper<T> {
-            const IMPLS: bool = true;
+/// Asserts at runtime that a type implements a logical trait expression, only
+/// when debug assertions are enabled.
+///
+/// See [`assert_impls_rt!`](macro.assert_impls_rt.html) for details.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// debug_assert_impls!(Vec<u8>: Clone & Default & !Copy);
+/// ```
+#[macro_export(local_inner_macros)]
+macro_rules! debug_assert_impls {
+    ($type:ty: $($trait_expr:tt)+) => {
+        if _impls_cfg!(debug_assertions) {
+            assert_impls_rt!($type: $($trait_expr)+);
         }
+    };
+}
 
-        <Wrapper<$type>>::IMPLS
-    }};
+/// Returns the value of the first arm whose logical trait expression a type
+/// implements.
+///
+/// Arms are written as in a `match`, and each expression uses the same
+/// grammar as [`impls!`]. The last arm must be `_`, which is used if no other
+/// arm matches. Leaving it out is a compile error rather than a silent
+/// default.
+///
+/// The selected value is picked by indexing an array of two values with the
+/// result of each expression, rather than by branching, so that it works in a
+/// `const` on Rust 1.37. As a result, the value of every arm is evaluated and
+/// values must be `Copy`. Values that are not, such as a `String`, can be
+/// selected as references, such as a `&'static str`, and converted after.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// const CHUNK: usize = impls_select!(u32 {
+///     Copy & !Drop => 64,
+///     Clone => 16,
+///     _ => 1,
+/// });
+/// assert_eq!(CHUNK, 64);
+///
+/// let strategy = impls_select!(Vec<u8> {
+///     Copy => "memcpy",
+///     Clone => "clone",
+///     _ => "move",
+/// });
+/// assert_eq!(strategy, "clone");
+/// ```
+///
+/// Without a `_` arm, this fails to compile:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate impls;
+/// const CHUNK: usize = impls_select!(u32 { Copy => 64 });
+/// ```
+///
+/// Nor does selecting a value that is not `Copy`:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate impls;
+/// let name = impls_select!(u32 {
+///     Clone => String::from("a"),
+///     _ => String::new(),
+/// });
+/// ```
+///
+/// [`impls!`]: macro.impls.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_select {
+    ($type:ty { $($arms:tt)* }) => {
+        _impls_select!(@arm [$type] $($arms)*)
+    };
+}
 
-    // NOT
-    ($type:ty: $(! !)* !$trait:path) => {
-        !_impls!($type: $trait)
+/// Builds the nested arrays of `impls_select`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_select {
+    (@arm $type:tt _ => $default:expr $(,)?) => {
+        $default
+    };
+    (@arm $type:tt _ => $default:expr, $($rest:tt)+) => {
+        _impls_compile_error!(
+            "the `_` arm of `impls_select!` must be the last arm"
+        )
+    };
+    (@arm $type:tt) => {
+        _impls_compile_error!(
+            "`impls_select!` requires a final `_ => value` arm"
+        )
+    };
+    (@arm $type:tt $($rest:tt)+) => {
+        _impls_select!(@expr $type [] $($rest)+)
     };
 
-    // PAREN
-    ($type:ty: $(! !)* ($($trait_expr:tt)+)) => {
-        _impls!($type: $($trait_expr)+)
+    // Collect the expression up to the `=>`.
+    (@expr $type:tt [$($trait_expr:tt)+] => $value:expr $(, $($rest:tt)*)?) => {
+        [
+            _impls_select!(@arm $type $($($rest)*)?),
+            $value,
+        ][_impls_select!(@bool $type: $($trait_expr)+) as usize]
     };
-    // PAREN+NOT
-    ($type:ty: $(! !)* !($($trait_expr:tt)+)) => {
-        !_impls!($type: $($trait_expr)+)
+    (@expr $type:tt [$($trait_expr:tt)*] $next:tt $($rest:tt)*) => {
+        _impls_select!(@expr $type [$($trait_expr)* $next] $($rest)*)
     };
-    // PAREN+OR
-    ($type:ty: $(! !)* ($($t1:tt)+) | $($t2:tt)+) => {
-        _impls!($type: $($t1)+)
-        |
-        _impls!($type: $($t2)+)
+    (@expr $type:tt [$($trait_expr:tt)*]) => {
+        _impls_compile_error!(
+            "expected `=> value` after the expression in `impls_select!`"
+        )
     };
-    // PAREN+OR+NOT
-    ($type:ty: $(! !)* !($($t1:tt)+) | $($t2:tt)+) => {
-        !_impls!($type: $($t1)+)
-        |
-        _impls!($type: $($t2)+)
+
+    (@bool [$type:ty]: $($trait_expr:tt)+) => {
+        _impls!(@bool $type: $($trait_expr)+)
     };
-    // PAREN+AND
-    ($type:ty: $(! !)* ($($t1:tt)+) & $($t2:tt)+) => {
-        _impls!($type: $($t1)+)
-        &
-        _impls!($type: $($t2)+)
+}
+
+/// Expands to one of two types depending on whether a type implements a
+/// logical trait expression.
+///
+/// This is the type-level counterpart of [`impls_select!`], written as
+/// `impls_type_if!(Type: EXPR => Then, else => Else)`. The `bool` from
+/// [`impls!`] becomes the length of the array type `[(); N]`, which is mapped
+/// to `Then` or `Else` by a trait. Unlike a `const bool` generic parameter,
+/// this works on stable Rust 1.37, the same as [`impls!`].
+///
+/// The checked type must not depend on generic parameters, since the `bool`
+/// is computed in a constant. The selected types can.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::collections::{BTreeMap, HashMap};
+/// use std::hash::Hash;
+///
+/// type Key = u32;
+/// type Map<V> = impls_type_if!(
+///     Key: Eq & Hash => HashMap<Key, V>,
+///     else => BTreeMap<Key, V>
+/// );
+///
+/// let mut map: Map<&str> = HashMap::new();
+/// map.insert(1, "one");
+/// ```
+///
+/// The selected type can be used anywhere a type can, such as in a field:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+/// use std::sync::Arc;
+///
+/// struct Shared {
+///     count: impls_type_if!(Rc<u8>: Send => Arc<u8>, else => Rc<u8>),
+/// }
+///
+/// let shared = Shared { count: Rc::new(1) };
+/// ```
+///
+/// [`impls!`]: macro.impls.html
+/// [`impls_select!`]: macro.impls_select.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_type_if {
+    ($type:ty: $($rest:tt)+) => {
+        _impls_type_if!(@expr [$type] [] $($rest)+)
     };
-    // PAREN+AND+NOT
-    ($type:ty: $(! !)* !($($t1:tt)+) & $($t2:tt)+) => {
-        !_impls!($type: $($t1)+)
-        &
-        _impls!($type: $($t2)+)
+}
+
+/// Collects the expression of `impls_type_if` up to the `=>`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_type_if {
+    (@expr $type:tt [$($trait_expr:tt)+]
+        => $then:ty, else => $else:ty $(,)?
+    ) => {
+        <[(); _impls_type_if!(@bool $type: $($trait_expr)+) as usize]
+            as $crate::_TypeIf<$then, $else>>::Output
     };
-    // PAREN+XOR
-    ($type:ty: $(! !)* ($($t1:tt)+) ^ $($t2:tt)+) => {
-        _impls!($type: $($t1)+)
-        ^
-        _impls!($type: $($t2)+)
+    (@expr $type:tt [$($trait_expr:tt)*] $next:tt $($rest:tt)+) => {
+        _impls_type_if!(@expr $type [$($trait_expr)* $next] $($rest)+)
     };
-    // PAREN+XOR+NOT
-    ($type:ty: $(! !)* !($($t1:tt)+) ^ $($t2:tt)+) => {
-        !_impls!($type: $($t1)+)
-        ^
-        _impls!($type: $($t2)+)
+    (@bool [$type:ty]: $($trait_expr:tt)+) => {
+        _impls!(@bool $type: $($trait_expr)+)
     };
+}
 
-    // OR: Any.
-    ($type:ty: $(! !)* $t1:path | $($t2:tt)+) => {{
-        _impls!($type: $t1)
-        |
-        _impls!($type: $($t2)+)
-    }};
-    // OR+NOT: Any.
-    ($type:ty: $(! !)* !$t1:path | $($t2:tt)+) => {{
-        !_impls!($type: $t1)
-        |
-        _impls!($type: $($t2)+)
-    }};
+/// Maps `[(); 1]` to `Then` and `[(); 0]` to `Else` for `impls_type_if`.
+#[doc(hidden)]
+pub trait _TypeIf<Then: ?Sized, Else: ?Sized> {
+    type Output: ?Sized;
+}
 
-    // AND: 0 lifetimes, 0 generics.
-    ($type:ty: $(! !)* $t1:ident & $($t2:tt)+) => {{
-        _impls!($type: $t1)
-        &
-        _impls!($type: $($t2)+)
-    }};
-    // AND+NOT: 0 lifetimes, 0 generics.
-    ($type:ty: $(! !)* !$t1:ident & $($t2:tt)+) => {{
-        !_impls!($type: $t1)
-        &
-        _impls!($type: $($t2)+)
-    }};
+impl<Then: ?Sized, Else: ?Sized> _TypeIf<Then, Else> for [(); 1] {
+    type Output = Then;
+}
 
-    // AND: 1+ lifetimes, 0+ generics.
-    (
-        $type:ty: $(! !)*
-        $t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
-        &
-        $($t2:tt)+
-    ) => {{
-        _impls!($type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >)
-        &
-        _impls!($type: $($t2)+)
-    }};
-    // AND+NOT: 1+ lifetimes, 0+ generics.
-    (
-        $type:ty: $(! !)*
-        !$t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
-        &
-        $($t2:tt)+
-    ) => {{
-        !_impls!($type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >)
-        &
-        _impls!($type: $($t2)+)
-    }};
+impl<Then: ?Sized, Else: ?Sized> _TypeIf<Then, Else> for [(); 0] {
+    type Output = Else;
+}
 
-    // AND: 0 lifetimes, 1+ generics.
-    (
-        $type:ty: $(! !)*
-        $t1:ident < $($t1_generic:ty),+ $(,)? >
-        &
-        $($t2:tt)+
-    ) => {{
-        _impls!($type: $t1 < $($t1_generic),+ >)
-        &
-        _impls!($type: $($t2)+)
-    }};
-    // AND+NOT: 0 lifetimes, 1+ generics.
-    (
-        $type:ty: $(! !)*
-        !$t1:ident < $($t1_generic:ty),+ $(,)? >
-        &
-        $($t2:tt)+
-    ) => {{
-        !_impls!($type: $t1 < $($t1_generic),+ >)
-        &
-        _impls!($type: $($t2)+)
-    }};
+/// Returns a `u64` whose bit `i` is set if a type implements the `i`th of up
+/// to 64 comma-separated logical trait expressions.
+///
+/// Each expression is evaluated once. The result is a constant, so it can be
+/// stored in `const` items, such as in metadata describing a type. More than
+/// 64 expressions fail to compile.
+///
+/// Expressions are split one token at a time, so long lists may need a higher
+/// `#![recursion_limit]` in the calling crate.
+///
+/// See also [`impls_count!`] and [`impls_first!`].
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+///
+/// const CAPABILITIES: u64 =
+///     impls_mask!(Rc<u8>: [Copy, Clone, Send, Sync, Default]);
+///
+/// assert_eq!(CAPABILITIES, 0b10010);
+/// assert_eq!(impls_mask!(u8: [Copy & Send, From<u16>]), 0b01);
+/// ```
+///
+/// [`impls_count!`]: macro.impls_count.html
+/// [`impls_first!`]: macro.impls_first.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_mask {
+    ($type:ty: [$($trait_exprs:tt)*]) => {
+        _impls_split!(_impls_bits [@mask [$type]] $($trait_exprs)*)
+    };
+}
 
-    // XOR: 0 lifetimes, 0 generics.
-    ($type:ty: $(! !)* $t1:ident ^ $($t2:tt)+) => {{
-        _impls!($type: $t1)
-        ^
-        _impls!($type: $($t2)+)
+/// Returns the number of comma-separated logical trait expressions that a
+/// type implements, as a `usize`.
+///
+/// Each expression is evaluated once, and the result is a constant.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// const COUNT: usize = impls_count!(String: [Copy, Clone, Send, Sync]);
+///
+/// assert_eq!(COUNT, 3);
+/// assert_eq!(impls_count!(str: [Sized, Clone]), 0);
+/// ```
+#[macro_export(local_inner_macros)]
+macro_rules! impls_count {
+    ($type:ty: [$($trait_exprs:tt)*]) => {
+        _impls_split!(_impls_bits [@count [$type]] $($trait_exprs)*)
+    };
+}
+
+/// Returns the index of the first comma-separated logical trait expression
+/// that a type implements, as an `Option<usize>`.
+///
+/// Each expression is evaluated once, and the result is a constant. To pick
+/// a value rather than an index, use [`impls_select!`].
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::cell::Cell;
+///
+/// const FIRST: Option<usize> = impls_first!(Cell<u8>: [Sync, Send, Copy]);
+///
+/// assert_eq!(FIRST, Some(1));
+/// assert_eq!(impls_first!(Cell<u8>: [Sync, Iterator]), None);
+/// ```
+///
+/// [`impls_select!`]: macro.impls_select.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_first {
+    ($type:ty: [$($trait_exprs:tt)*]) => {
+        _impls_split!(_impls_bits [@first [$type]] $($trait_exprs)*)
+    };
+}
+
+/// Builds the output of `impls_mask`, `impls_count`, and `impls_first` from
+/// split expressions.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_bits {
+    (@mask $type:tt $trait_exprs:tt) => {{
+        const MASK: u64 = _impls_bits!(@shift $type [] $trait_exprs);
+        MASK
     }};
-    // XOR+NOT: 0 lifetimes, 0 generics.
-    ($type:ty: $(! !)* !$t1:ident ^ $($t2:tt)+) => {{
-        ! _impls!($type: $t1)
-        ^
-        _impls!($type: $($t2)+)
+    (@count $type:tt [$(($($trait_expr:tt)+))*]) => {{
+        const COUNT: usize =
+            0 $(+ _impls_bits!(@bool $type: $($trait_expr)+) as usize)*;
+        COUNT
     }};
-
-    // XOR: 1+ lifetimes, 0+ generics.
-    (
-        $type:ty: $(! !)*
-        $t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
-        ^
-        $($t2:tt)+
-    ) => {{
-        _impls!($type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >)
-        ^
-        _impls!($type: $($t2)+)
+    (@first $type:tt $trait_exprs:tt) => {{
+        // The index of the first match, or the length if none match.
+        const INDEX: usize = _impls_bits!(@index $type $trait_exprs);
+        const LEN: usize = _impls_bits!(@len $trait_exprs);
+        const FIRST: $crate::_core::option::Option<usize> = [
+            $crate::_core::option::Option::Some(INDEX),
+            $crate::_core::option::Option::None,
+        ][(INDEX == LEN) as usize];
+        FIRST
     }};
+
+    // Shifting by 64 or more fails to compile.
+    (@shift $type:tt [$($shift:tt)*] [($($trait_expr:tt)+) $($rest:tt)*]) => {
+        (_impls_bits!(@bool $type: $($trait_expr)+) as u64) << (0 $($shift)*)
+            | _impls_bits!(@shift $type [$($shift)* + 1] [$($rest)*])
+    };
+    (@shift $type:tt $shift:tt []) => {
+        0
+    };
+
+    // Multiplying by zero stops counting at the first match without
+    // branching, which constants cannot do before Rust 1.46.
+    (@index $type:tt [($($trait_expr:tt)+) $($rest:tt)*]) => {
+        (!_impls_bits!(@bool $type: $($trait_expr)+) as usize)
+            * (1 + _impls_bits!(@index $type [$($rest)*]))
+    };
+    (@index $type:tt []) => {
+        0
+    };
+
+    (@len [$($trait_expr:tt)*]) => {
+        0 $(+ _impls_bits!(@one $trait_expr))*
+    };
+    (@one $trait_expr:tt) => {
+        1
+    };
+    (@bool [$type:ty]: $($trait_expr:tt)+) => {
+        _impls!(@bool $type: $($trait_expr)+)
+    };
+}
+
+/// Runs a closure over a value as a trait object only if its type implements
+/// the trait, and an `else` closure otherwise.
+///
+/// Unlike branching on [`impls!`], the first closure typechecks for every
+/// type, since it receives the value as `&dyn Trait`. The branch is selected
+/// at compile-time by [`try_as_dyn!`], which uses the same trick as
+/// [`impls!`]. This lets optional integrations, such as logging a value if it
+/// is `Debug`, work without cargo features.
+///
+/// The value is borrowed, as with [`format_args!`]. The bounds are written as
+/// in a trait object type, such as `Debug + Send`, so they must be a trait
+/// that can be made into an object, followed by any auto traits. The `else`
+/// closure receives a reference to the value and can be omitted if the first
+/// closure returns `()`. Both are regular closures, so they can capture
+/// variables and their return types are inferred.
+///
+/// Bounds that cannot be made into an object, such as `Clone`, are written
+/// with `fn` before the first closure, as in `fn |v| -> u8 { ... }`. It is
+/// then compiled as a generic function over the bounds, written as in a
+/// `where` clause, and receives a reference to the value. Being a function,
+/// it cannot capture variables, and it must declare its return type unless it
+/// returns `()`. Without `fn`, using common traits such as `Clone`, `Default`,
+/// or `Hash` in the bounds is a compile error.
+///
+/// Like [`impls!`], the branch is selected for the type as written at the call
+/// site, so values whose types are generic parameters always select `else`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::fmt::Debug;
+///
+/// struct Opaque;
+///
+/// let prefix = "value = ";
+///
+/// let debug = if_impls!([1, 2]: Debug => |v| format!("{}{:?}", prefix, v),
+///     else => |_| "<opaque>".into());
+///
+/// assert_eq!(debug, "value = [1, 2]");
+///
+/// let debug = if_impls!(Opaque: Debug => |v| format!("{}{:?}", prefix, v),
+///     else => |_| "<opaque>".into());
+///
+/// assert_eq!(debug, "<opaque>");
+/// ```
+///
+/// Without `else`, the closure only runs if the bounds hold:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// # use std::fmt::Debug;
+/// let value = 42;
+/// if_impls!(value: Debug => |v| println!("value = {:?}", v));
+/// ```
+///
+/// With `fn`, the bounds can include traits such as `Clone`:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::fmt::Debug;
+///
+/// let copies = if_impls!(vec![1, 2]: Clone + Debug => fn |v| -> String {
+///     format!("{:?}", (v.clone(), v.clone()))
+/// }, else => |_| String::new());
+///
+/// assert_eq!(copies, "([1, 2], [1, 2])");
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate impls;
+/// // `Clone` cannot be made into an object.
+/// if_impls!(5u8: Clone => |v| drop(v.clone()));
+/// ```
+///
+/// [`impls!`]: macro.impls.html
+/// [`try_as_dyn!`]: macro.try_as_dyn.html
+/// [`format_args!`]: https://doc.rust-lang.org/core/macro.format_args.html
+#[macro_export(local_inner_macros)]
+macro_rules! if_impls {
+    ($($input:tt)+) => {
+        _if_impls!(@value [] $($input)+)
+    };
+}
+
+/// Parses the input of `if_impls` and selects its branch.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _if_impls {
+    // Collect the value up to the `:` and the bounds up to the `=>`.
+    (@value [$($value:tt)+] : $($rest:tt)+) => {
+        _if_impls!(@bounds [$($value)+] [] $($rest)+)
+    };
+    (@value [$($value:tt)*] $next:tt $($rest:tt)+) => {
+        _if_impls!(@value [$($value)* $next] $($rest)+)
+    };
+    (@bounds $value:tt [$($bounds:tt)+] => $($rest:tt)+) => {
+        _if_impls!(@then $value [$($bounds)+] $($rest)+)
+    };
+    (@bounds $value:tt [$($bounds:tt)*] $next:tt $($rest:tt)+) => {
+        _if_impls!(@bounds $value [$($bounds)* $next] $($rest)+)
+    };
+
+    (@then $value:tt $bounds:tt
+        fn |$arg:pat| -> $ret:ty $body:block
+        $(, else => $else:expr)? $(,)?
+    ) => {
+        _if_impls!(@fn $value $bounds [$arg] [$ret] [$body] [$($else)?])
+    };
+    (@then $value:tt $bounds:tt
+        fn |$arg:pat| $body:expr
+        $(, else => $else:expr)? $(,)?
+    ) => {
+        _if_impls!(@fn $value $bounds [$arg] [()] [$body] [$($else)?])
+    };
+    (@then $value:tt [$($bounds:tt)+] $($rest:tt)+) => {
+        _if_impls!(@object_safe [$($bounds)+] $value [$($bounds)+] $($rest)+)
+    };
+
+    // Common traits that cannot be made into objects are rejected with an
+    // error that names the `fn` form, rather than one about `dyn`.
+    (@object_safe [] $value:tt $bounds:tt $($rest:tt)+) => {
+        _if_impls!(@dyn $value $bounds $($rest)+)
+    };
+    (@object_safe [PartialEq < $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@object_safe [$($tail)*] $($rest)+)
+    };
+    (@object_safe [PartialOrd < $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@object_safe [$($tail)*] $($rest)+)
+    };
+    (@object_safe [Clone $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Clone)
+    };
+    (@object_safe [Copy $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Copy)
+    };
+    (@object_safe [Default $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Default)
+    };
+    (@object_safe [Sized $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Sized)
+    };
+    (@object_safe [Hash $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Hash)
+    };
+    (@object_safe [Eq $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Eq)
+    };
+    (@object_safe [Ord $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe Ord)
+    };
+    (@object_safe [PartialEq $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe PartialEq)
+    };
+    (@object_safe [PartialOrd $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@not_object_safe PartialOrd)
+    };
+    (@object_safe [$next:tt $($tail:tt)*] $($rest:tt)+) => {
+        _if_impls!(@object_safe [$($tail)*] $($rest)+)
+    };
+    (@not_object_safe $trait:ident) => {
+        _impls_compile_error!(_impls_concat!(
+            "`",
+            _impls_stringify!($trait),
+            "` cannot be made into an object, so `if_impls!` needs the ",
+            "`fn |v| ...` form for it"
+        ))
+    };
+
+    (@dyn [$($value:tt)+] [$($bounds:tt)+]
+        $then:expr $(, else => $else:expr)? $(,)?
+    ) => {
+        // Matching keeps temporaries alive until the end of the statement.
+        match &$($value)+ {
+            __value => $crate::_if_impls(
+                __value,
+                try_as_dyn!(__value => dyn $($bounds)+),
+                $then,
+                _if_impls!(@else [$($else)?]),
+            ),
+        }
+    };
+
+    (@else []) => {
+        |_| {}
+    };
+    (@else [$else:expr]) => {
+        $else
+    };
+
+    (@fn [$($value:tt)+] [$($bounds:tt)+] [$arg:pat] [$ret:ty] [$body:expr]
+        $else:tt
+    ) => {
+        match &$($value)+ {
+            __value => {
+                // Names are prefixed since `$body` is expanded within this
+                // block.
+                #[allow(dead_code)]
+                fn __if_impls_then<__T>($arg: &__T) -> $ret
+                where
+                    __T: ?Sized + $($bounds)+,
+                {
+                    $body
+                }
+
+                /// Calls the `else` closure if the value does not satisfy the
+                /// bounds.
+                trait __IfImplsElse<'a, __T: 'a + ?Sized> {
+                    fn __if_impls<__F>(self, f: __F) -> $ret
+                    where
+                        __F: $crate::_core::ops::FnOnce(&'a __T) -> $ret;
+                }
+
+                struct __IfImpls<'a, __T: 'a + ?Sized>(&'a __T);
+
+                impl<'a, __T: 'a + ?Sized> __IfImplsElse<'a, __T>
+                    for __IfImpls<'a, __T>
+                {
+                    fn __if_impls<__F>(self, f: __F) -> $ret
+                    where
+                        __F: $crate::_core::ops::FnOnce(&'a __T) -> $ret,
+                    {
+                        f(self.0)
+                    }
+                }
+
+                /// Takes priority over `__IfImplsElse` if the value satisfies
+                /// the bounds.
+                #[allow(dead_code)]
+                impl<'a, __T> __IfImpls<'a, __T>
+                where
+                    __T: 'a + ?Sized + $($bounds)+,
+                {
+                    fn __if_impls<__F>(self, _: __F) -> $ret
+                    where
+                        __F: $crate::_core::ops::FnOnce(&'a __T) -> $ret,
+                    {
+                        __if_impls_then(self.0)
+                    }
+                }
+
+                __IfImpls(__value).__if_impls(_if_impls!(@else $else))
+            }
+        }
+    };
+}
+
+/// Calls `then` with the trait object if there is one, or `otherwise` with
+/// the value, for `if_impls`.
+///
+/// Taking the closures as arguments lets their parameter types be inferred.
+#[doc(hidden)]
+pub fn _if_impls<'a, T, D, R, F, E>(
+    value: &'a T,
+    dyn_value: Option<&'a D>,
+    then: F,
+    otherwise: E,
+) -> R
+where
+    T: ?Sized,
+    D: ?Sized,
+    F: FnOnce(&'a D) -> R,
+    E: FnOnce(&'a T) -> R,
+{
+    match dyn_value {
+        Some(dyn_value) => then(dyn_value),
+        None => otherwise(value),
+    }
+}
+
+/// Returns a reference to a value as a trait object if its type implements the
+/// trait, or `None` otherwise.
+///
+/// This uses the same trick as [`impls!`] to select at compile-time between a
+/// method that coerces the value and a fallback method. This is useful in
+/// logging and plugin layers that handle values of any type, but can do more
+/// with some.
+///
+/// There are three forms:
+///
+/// - `try_as_dyn!(&value => dyn Trait)` returns `Option<&dyn Trait>`.
+/// - `try_as_dyn!(&mut value => dyn Trait)` returns `Option<&mut dyn Trait>`.
+/// - `try_as_dyn!(boxed => Box<dyn Trait>)` returns
+///   `Result<Box<dyn Trait>, Box<T>>`, which gives back the box on failure.
+///   This requires the `alloc` feature.
+///
+/// Like [`impls!`], the result depends on the type as written at the call
+/// site, so values whose types are generic parameters always return `None`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::fmt::Display;
+///
+/// struct Opaque;
+///
+/// let name = String::from("impls");
+///
+/// let shown = try_as_dyn!(&name => dyn Display).map(|d| d.to_string());
+/// assert_eq!(shown.as_ref().map(String::as_str), Some("impls"));
+///
+/// assert!(try_as_dyn!(&Opaque => dyn Display).is_none());
+/// ```
+///
+/// Mutable references work the same way:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::fmt::Write;
+///
+/// let mut out = String::new();
+/// if let Some(writer) = try_as_dyn!(&mut out => dyn Write) {
+///     writer.write_str("written").unwrap();
+/// }
+/// assert_eq!(out, "written");
+/// ```
+///
+/// [`impls!`]: macro.impls.html
+#[macro_export(local_inner_macros)]
+macro_rules! try_as_dyn {
+    (&mut $value:expr => dyn $($trait:tt)+) => {
+        _try_as_dyn!(@mut [&mut $value] [$($trait)+])
+    };
+    ($value:expr => Box<dyn $($rest:tt)+) => {
+        _try_as_dyn!(@box [$value] [] $($rest)+)
+    };
+    ($value:expr => dyn $($trait:tt)+) => {
+        _try_as_dyn!(@ref [$value] [$($trait)+])
+    };
+}
+
+/// Builds the output of `try_as_dyn`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _try_as_dyn {
+    (@ref [$value:expr] [$($trait:tt)+]) => {{
+        // Parentheses are needed for multiple bounds, as in
+        // `&(dyn Display + Sync)`.
+        struct __TryAsDyn<'a, __T: ?Sized + 'a>(&'a __T);
+
+        /// Returns `None` if the type does not implement the trait.
+        #[allow(unused_parens)]
+        trait __TryAsDynNone<'a> {
+            fn __try_as_dyn(
+                self,
+            ) -> $crate::_core::option::Option<&'a (dyn $($trait)+)>;
+        }
+
+        #[allow(unused_parens)]
+        impl<'a, __T: 'a + ?Sized> __TryAsDynNone<'a> for __TryAsDyn<'a, __T> {
+            fn __try_as_dyn(
+                self,
+            ) -> $crate::_core::option::Option<&'a (dyn $($trait)+)> {
+                $crate::_core::option::Option::None
+            }
+        }
+
+        /// Takes priority over `__TryAsDynNone` if the type implements the
+        /// trait.
+        #[allow(dead_code, unused_parens)]
+        impl<'a, __T> __TryAsDyn<'a, __T>
+        where
+            __T: $($trait)+,
+        {
+            fn __try_as_dyn(
+                self,
+            ) -> $crate::_core::option::Option<&'a (dyn $($trait)+)> {
+                $crate::_core::option::Option::Some(self.0)
+            }
+        }
+
+        __TryAsDyn($value).__try_as_dyn()
+    }};
+    (@mut [$value:expr] [$($trait:tt)+]) => {{
+        struct __TryAsDyn<'a, __T: ?Sized + 'a>(&'a mut __T);
+
+        /// Returns `None` if the type does not implement the trait.
+        #[allow(unused_parens)]
+        trait __TryAsDynNone<'a> {
+            fn __try_as_dyn(
+                self,
+            ) -> $crate::_core::option::Option<&'a mut (dyn $($trait)+)>;
+        }
+
+        #[allow(unused_parens)]
+        impl<'a, __T: 'a + ?Sized> __TryAsDynNone<'a> for __TryAsDyn<'a, __T> {
+            fn __try_as_dyn(
+                self,
+            ) -> $crate::_core::option::Option<&'a mut (dyn $($trait)+)> {
+                $crate::_core::option::Option::None
+            }
+        }
+
+        /// Takes priority over `__TryAsDynNone` if the type implements the
+        /// trait.
+        #[allow(dead_code, unused_parens)]
+        impl<'a, __T> __TryAsDyn<'a, __T>
+        where
+            __T: $($trait)+,
+        {
+            fn __try_as_dyn(
+                self,
+            ) -> $crate::_core::option::Option<&'a mut (dyn $($trait)+)> {
+                $crate::_core::option::Option::Some(self.0)
+            }
+        }
+
+        __TryAsDyn($value).__try_as_dyn()
+    }};
+
+    // Remove the `>` that closes `Box<`, splitting `>>` if the trait ends with
+    // generic arguments.
+    (@box $value:tt [$($trait:tt)+] >) => {
+        _try_as_dyn_box!($value [$($trait)+])
+    };
+    (@box $value:tt [$($trait:tt)+] >>) => {
+        _try_as_dyn_box!($value [$($trait)+ >])
+    };
+    (@box $value:tt [$($trait:tt)*] $next:tt $($rest:tt)+) => {
+        _try_as_dyn!(@box $value [$($trait)* $next] $($rest)+)
+    };
+}
+
+/// Builds the output of `try_as_dyn` for boxes if the `alloc` feature is
+/// enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _try_as_dyn_box {
+    ([$value:expr] [$($trait:tt)+]) => {{
+        use $crate::_alloc::boxed::Box as __Box;
+        use $crate::_core::result::Result as __Result;
+
+        struct __TryAsDyn<__T: ?Sized>(__Box<__T>);
+
+        /// Returns the box if the type does not implement the trait.
+        trait __TryAsDynNone<__T: ?Sized> {
+            fn __try_as_dyn(
+                self,
+            ) -> __Result<__Box<dyn $($trait)+>, __Box<__T>>;
+        }
+
+        impl<__T: ?Sized> __TryAsDynNone<__T> for __TryAsDyn<__T> {
+            fn __try_as_dyn(
+                self,
+            ) -> __Result<__Box<dyn $($trait)+>, __Box<__T>> {
+                __Result::Err(self.0)
+            }
+        }
+
+        /// Takes priority over `__TryAsDynNone` if the type implements the
+        /// trait.
+        #[allow(dead_code)]
+        impl<__T> __TryAsDyn<__T>
+        where
+            __T: $($trait)+,
+            __T: 'static,
+        {
+            fn __try_as_dyn(
+                self,
+            ) -> __Result<__Box<dyn $($trait)+>, __Box<__T>> {
+                __Result::Ok(self.0)
+            }
+        }
+
+        __TryAsDyn($value).__try_as_dyn()
+    }};
+}
+
+/// Reports that boxes require the `alloc` feature.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _try_as_dyn_box {
+    ($value:tt $trait:tt) => {
+        _impls_compile_error!(
+            "`try_as_dyn!` requires the `alloc` feature of `impls` for `Box`"
+        )
+    };
+}
+
+/// Returns the [`&'static Explanation`] of how a type evaluates a logical trait
+/// expression.
+///
+/// The evaluation tree is built from the same parse as [`impls!`], so each node
+/// reflects exactly how the resulting `bool` is computed. Each node holds its
+/// operator or trait, and its value.
+///
+/// Traits can optionally be labeled after a `;`. Labels are shown next to
+/// their traits when rendering.
+///
+/// Because the tree is a constant, the type must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// let explanation = impls_explain!(String: Clone & !Copy);
+/// assert!(explanation.value());
+///
+/// let labeled = impls_explain!(
+///     String: Clone ^ Copy;
+///     Clone => "duplicable",
+///     Copy => "bitwise copyable",
+/// );
+///
+/// assert_eq!(labeled.to_string(), "\
+/// String: Clone ^ Copy = true
+/// xor = true
+/// ├── Clone = true (duplicable)
+/// └── Copy = false (bitwise copyable)");
+/// ```
+///
+/// Rendering does not allocate, so it can write into any [`fmt::Write`]:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::fmt::Write;
+///
+/// let mut out = String::new();
+/// impls_explain!(u8: Send ^ Sync).write_to(&mut out).unwrap();
+/// ```
+///
+/// [`&'static Explanation`]: explain/struct.Explanation.html
+/// [`impls!`]: macro.impls.html
+/// [`fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_explain {
+    ($type:ty: $($trait_expr:tt)+) => {
+        _impls_explain!(@split $type; [] $($trait_expr)+)
+    };
+}
+
+/// Splits the input of `impls_explain` at `;` into the expression and labels.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_explain {
+    (
+        @split $type:ty; [$($trait_expr:tt)+];
+        $($leaf:path => $label:expr),* $(,)?
+    ) => {{
+        const EXPLANATION: &$crate::explain::Explanation<'static> =
+            &$crate::explain::Explanation {
+                ty: _impls_stringify!($type),
+                expr: _impls_stringify!($($trait_expr)+),
+                root: &_impls!(@node $type: $($trait_expr)+),
+                labels: &[$((_impls_stringify!($leaf), $label)),*],
+            };
+        EXPLANATION
+    }};
+    (@split $type:ty; [$($trait_expr:tt)*] $next:tt $($rest:tt)*) => {
+        _impls_explain!(@split $type; [$($trait_expr)* $next] $($rest)*)
+    };
+    (@split $type:ty; [$($trait_expr:tt)+]) => {
+        _impls_explain!(@split $type; [$($trait_expr)+];)
+    };
+}
+
+/// Returns the [`&'static TypeExplanation`] of how a type and each of its
+/// generic arguments evaluate a logical trait expression.
+///
+/// The type is walked as written: the arguments of paths such as `Vec<T>`, the
+/// elements of tuples, arrays, and slices, and the referents of references and
+/// pointers are each evaluated over the same expression. The innermost types
+/// that have the same value as the whole type are where the answer flips, and
+/// are marked when rendering.
+///
+/// Lifetimes and const arguments are skipped, and trait objects, function
+/// pointers, and qualified paths such as `<T as Trait>::Output` are not walked
+/// into.
+///
+/// Because the tree is a constant, the type must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+/// use std::sync::{Arc, Mutex};
+///
+/// let explanation = explain_type!(Arc<Mutex<Vec<Rc<u8>>>>: Send);
+/// assert!(!explanation.value());
+///
+/// let mut flips = Vec::new();
+/// explanation.for_each_flip_point(|node| flips.push(node.ty));
+/// assert_eq!(flips, ["Rc<u8>"]);
+///
+/// assert_eq!(explanation.to_string(), "\
+/// Arc<Mutex<Vec<Rc<u8>>>>: Send = false
+/// └── Mutex<Vec<Rc<u8>>> = false
+///     └── Vec<Rc<u8>> = false
+///         └── Rc<u8> = false (flips)
+///             └── u8 = true");
+/// ```
+///
+/// [`&'static TypeExplanation`]: explain/struct.TypeExplanation.html
+#[macro_export(local_inner_macros)]
+macro_rules! explain_type {
+    ($($input:tt)+) => {
+        _explain_type!(@split [] $($input)+)
+    };
+}
+
+/// Walks the generic arguments of the type given to `explain_type`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _explain_type {
+    // Split the input at `:` into the type and the expression.
+    (@split [$($type:tt)+] : $($trait_expr:tt)+) => {{
+        const EXPLANATION: &$crate::explain::TypeExplanation<'static> =
+            &$crate::explain::TypeExplanation {
+                expr: _impls_stringify!($($trait_expr)+),
+                root: &_explain_type!(@node [$($trait_expr)+] $($type)+),
+            };
+        EXPLANATION
+    }};
+    (@split [$($type:tt)*] $next:tt $($rest:tt)*) => {
+        _explain_type!(@split [$($type)* $next] $($rest)*)
+    };
+
+    (@node $expr:tt $($type:tt)+) => {
+        $crate::explain::TypeNode {
+            ty: _explain_type!(@stringify $($type)+),
+            value: _explain_type!(@value [$($type)+] $expr),
+            args: _explain_type!(@args $expr [$($type)+]),
+        }
+    };
+    // Types are parsed before being stringified, which spaces their tokens
+    // the same way on every compiler version.
+    (@stringify $type:ty) => {
+        _impls_stringify!($type)
+    };
+    (@value [$($type:tt)+] [$($trait_expr:tt)+]) => {
+        _impls!(@bool $($type)+: $($trait_expr)+)
+    };
+
+    // References and pointers.
+    (@args $expr:tt [&& $($type:tt)+]) => {
+        &[_explain_type!(@node $expr & $($type)+)]
+    };
+    (@args $expr:tt [& $lt:lifetime mut $($type:tt)+]) => {
+        &[_explain_type!(@node $expr $($type)+)]
+    };
+    (@args $expr:tt [& $lt:lifetime $($type:tt)+]) => {
+        &[_explain_type!(@node $expr $($type)+)]
+    };
+    (@args $expr:tt [& mut $($type:tt)+]) => {
+        &[_explain_type!(@node $expr $($type)+)]
+    };
+    (@args $expr:tt [& $($type:tt)+]) => {
+        &[_explain_type!(@node $expr $($type)+)]
+    };
+    (@args $expr:tt [* const $($type:tt)+]) => {
+        &[_explain_type!(@node $expr $($type)+)]
+    };
+    (@args $expr:tt [* mut $($type:tt)+]) => {
+        &[_explain_type!(@node $expr $($type)+)]
+    };
+
+    // Arrays, slices, and tuples.
+    (@args $expr:tt [[$($inner:tt)+]]) => {
+        _explain_type!(@array $expr [] $($inner)+)
+    };
+    (@args $expr:tt [($($inner:tt)*)]) => {
+        _explain_type!(@list $expr [] [] [] $($inner)*)
+    };
+
+    // Types that are not walked into.
+    (@args $expr:tt [dyn $($type:tt)+]) => { &[] };
+    (@args $expr:tt [impl $($type:tt)+]) => { &[] };
+    (@args $expr:tt [< $($type:tt)+]) => { &[] };
+
+    // Paths, with or without generic arguments.
+    (@args $expr:tt [$($type:tt)+]) => {
+        _explain_type!(@path $expr $($type)+)
+    };
+
+    (@array $expr:tt [$($elem:tt)+] ; $($len:tt)+) => {
+        &[_explain_type!(@node $expr $($elem)+)]
+    };
+    (@array $expr:tt [$($elem:tt)+]) => {
+        &[_explain_type!(@node $expr $($elem)+)]
+    };
+    (@array $expr:tt [$($elem:tt)*] $next:tt $($rest:tt)*) => {
+        _explain_type!(@array $expr [$($elem)* $next] $($rest)*)
+    };
+
+    // Find the generic arguments, skipping function-like paths such as
+    // `fn(u8) -> u8` and `Fn(u8)`.
+    (@path $expr:tt < $($rest:tt)+) => {
+        _explain_type!(@list $expr [] [] [] $($rest)+)
+    };
+    (@path $expr:tt ($($inputs:tt)*) $($rest:tt)*) => { &[] };
+    (@path $expr:tt $next:tt $($rest:tt)*) => {
+        _explain_type!(@path $expr $($rest)*)
+    };
+    (@path $expr:tt) => { &[] };
+
+    // Split comma-separated arguments into nodes in a single pass, ending at
+    // the closing `>` of generic arguments or at the end of a tuple.
+    (@list $expr:tt [] [] [$($nodes:tt)*]) => {
+        &[$($nodes)*]
+    };
+    (@list $expr:tt [] [$($arg:tt)+] $nodes:tt) => {
+        _explain_type!(@push $expr [$($arg)+] $nodes)
+    };
+    (@list $expr:tt [] [$($arg:tt)+] $nodes:tt , $($rest:tt)*) => {
+        _explain_type!(@push $expr [$($arg)+] $nodes $($rest)*)
+    };
+    (@list $expr:tt $depth:tt $arg:tt $nodes:tt $next:tt $($rest:tt)*) => {
+        _explain_type!(@tok [$next] $next $expr $depth $arg $nodes $($rest)*)
+    };
+
+    // Track angle bracket depth while keeping each token as written.
+    (
+        @tok [$t:tt] < $expr:tt [$($depth:tt)*] [$($arg:tt)*] $nodes:tt
+        $($rest:tt)*
+    ) => {
+        _explain_type!(
+            @list $expr [< $($depth)*] [$($arg)* $t] $nodes $($rest)*
+        )
+    };
+    (@tok [$t:tt] > $expr:tt [] $arg:tt $nodes:tt) => {
+        _explain_type!(@push $expr $arg $nodes)
+    };
+    (@tok [$t:tt] >> $expr:tt [$d:tt] [$($arg:tt)*] $nodes:tt) => {
+        _explain_type!(@push $expr [$($arg)* >] $nodes)
+    };
+    // Trailing tokens, such as in `Vec<u8>::IntoIter`.
+    (@tok [$t:tt] > $expr:tt [] $arg:tt $nodes:tt $($rest:tt)+) => { &[] };
+    (@tok [$t:tt] >> $expr:tt [$d:tt] $arg:tt $nodes:tt $($rest:tt)+) => {
+        &[]
+    };
+    (
+        @tok [$t:tt] > $expr:tt [$d:tt $($depth:tt)*] [$($arg:tt)*] $nodes:tt
+        $($rest:tt)*
+    ) => {
+        _explain_type!(@list $expr [$($depth)*] [$($arg)* $t] $nodes $($rest)*)
+    };
+    (
+        @tok [$t:tt] >> $expr:tt [$d1:tt $d2:tt $($depth:tt)*] [$($arg:tt)*]
+        $nodes:tt $($rest:tt)*
+    ) => {
+        _explain_type!(@list $expr [$($depth)*] [$($arg)* $t] $nodes $($rest)*)
+    };
+    (
+        @tok [$t:tt] $_t:tt $expr:tt $depth:tt [$($arg:tt)*] $nodes:tt
+        $($rest:tt)*
+    ) => {
+        _explain_type!(@list $expr $depth [$($arg)* $t] $nodes $($rest)*)
+    };
+
+    // Turn an argument into a node, skipping lifetimes and consts.
+    (@push $expr:tt [$lt:lifetime] $nodes:tt $($rest:tt)*) => {
+        _explain_type!(@list $expr [] [] $nodes $($rest)*)
+    };
+    (@push $expr:tt [$lit:literal] $nodes:tt $($rest:tt)*) => {
+        _explain_type!(@list $expr [] [] $nodes $($rest)*)
+    };
+    (@push $expr:tt [- $lit:literal] $nodes:tt $($rest:tt)*) => {
+        _explain_type!(@list $expr [] [] $nodes $($rest)*)
+    };
+    (@push $expr:tt [{$($c:tt)*}] $nodes:tt $($rest:tt)*) => {
+        _explain_type!(@list $expr [] [] $nodes $($rest)*)
+    };
+    // Associated type bindings, such as `Item = u8`.
+    (
+        @push $expr:tt [$name:ident = $($type:tt)+] [$($nodes:tt)*]
+        $($rest:tt)*
+    ) => {
+        _explain_type!(
+            @list $expr [] []
+            [$($nodes)* _explain_type!(@node $expr $($type)+),]
+            $($rest)*
+        )
+    };
+    (@push $expr:tt [$($type:tt)+] [$($nodes:tt)*] $($rest:tt)*) => {
+        _explain_type!(
+            @list $expr [] []
+            [$($nodes)* _explain_type!(@node $expr $($type)+),]
+            $($rest)*
+        )
+    };
+}
+
+/// Handles the dirty work of `impls`.
+///
+/// Every rule takes an output mode (`@bool` or `@node`) that is forwarded to
+/// `_impls_emit!`. This way, all outputs share the exact same parse.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls {
+    // ONE: Turn `$trait` into `true` or `false` based on whether `$type`
+    // implements it.
+    (@$m:ident $type:ty: $(! !)* $trait:path) => {
+        _impls_emit!(@$m leaf $type: $trait)
+    };
+
+    // NOT
+    (@$m:ident $type:ty: $(! !)* !$trait:path) => {
+        _impls_emit!(@$m not _impls!(@$m $type: $trait))
+    };
+
+    // PAREN
+    (@$m:ident $type:ty: $(! !)* ($($trait_expr:tt)+)) => {
+        _impls!(@$m $type: $($trait_expr)+)
+    };
+    // PAREN+NOT
+    (@$m:ident $type:ty: $(! !)* !($($trait_expr:tt)+)) => {
+        _impls_emit!(@$m not _impls!(@$m $type: $($trait_expr)+))
+    };
+    // PAREN+OR
+    (@$m:ident $type:ty: $(! !)* ($($t1:tt)+) | $($t2:tt)+) => {
+        _impls_emit!(@$m or
+            _impls!(@$m $type: $($t1)+),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // PAREN+OR+NOT
+    (@$m:ident $type:ty: $(! !)* !($($t1:tt)+) | $($t2:tt)+) => {
+        _impls_emit!(@$m or
+            _impls_emit!(@$m not _impls!(@$m $type: $($t1)+)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // PAREN+AND
+    (@$m:ident $type:ty: $(! !)* ($($t1:tt)+) & $($t2:tt)+) => {
+        _impls_emit!(@$m and
+            _impls!(@$m $type: $($t1)+),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // PAREN+AND+NOT
+    (@$m:ident $type:ty: $(! !)* !($($t1:tt)+) & $($t2:tt)+) => {
+        _impls_emit!(@$m and
+            _impls_emit!(@$m not _impls!(@$m $type: $($t1)+)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // PAREN+XOR
+    (@$m:ident $type:ty: $(! !)* ($($t1:tt)+) ^ $($t2:tt)+) => {
+        _impls_emit!(@$m xor
+            _impls!(@$m $type: $($t1)+),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // PAREN+XOR+NOT
+    (@$m:ident $type:ty: $(! !)* !($($t1:tt)+) ^ $($t2:tt)+) => {
+        _impls_emit!(@$m xor
+            _impls_emit!(@$m not _impls!(@$m $type: $($t1)+)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // OR: Any.
+    (@$m:ident $type:ty: $(! !)* $t1:path | $($t2:tt)+) => {
+        _impls_emit!(@$m or
+            _impls!(@$m $type: $t1),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // OR+NOT: Any.
+    (@$m:ident $type:ty: $(! !)* !$t1:path | $($t2:tt)+) => {
+        _impls_emit!(@$m or
+            _impls_emit!(@$m not _impls!(@$m $type: $t1)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // AND: 0 lifetimes, 0 generics.
+    (@$m:ident $type:ty: $(! !)* $t1:ident & $($t2:tt)+) => {
+        _impls_emit!(@$m and
+            _impls!(@$m $type: $t1),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // AND+NOT: 0 lifetimes, 0 generics.
+    (@$m:ident $type:ty: $(! !)* !$t1:ident & $($t2:tt)+) => {
+        _impls_emit!(@$m and
+            _impls_emit!(@$m not _impls!(@$m $type: $t1)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // AND: 1+ lifetimes, 0+ generics.
+    (
+        @$m:ident $type:ty: $(! !)*
+        $t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
+        &
+        $($t2:tt)+
+    ) => {
+        _impls_emit!(@$m and
+            _impls!(@$m $type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // AND+NOT: 1+ lifetimes, 0+ generics.
+    (
+        @$m:ident $type:ty: $(! !)*
+        !$t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
+        &
+        $($t2:tt)+
+    ) => {
+        _impls_emit!(@$m and
+            _impls_emit!(@$m not
+                _impls!(@$m $type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >)
+            ),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // AND: 0 lifetimes, 1+ generics.
+    (
+        @$m:ident $type:ty: $(! !)*
+        $t1:ident < $($t1_generic:ty),+ $(,)? >
+        &
+        $($t2:tt)+
+    ) => {
+        _impls_emit!(@$m and
+            _impls!(@$m $type: $t1 < $($t1_generic),+ >),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // AND+NOT: 0 lifetimes, 1+ generics.
+    (
+        @$m:ident $type:ty: $(! !)*
+        !$t1:ident < $($t1_generic:ty),+ $(,)? >
+        &
+        $($t2:tt)+
+    ) => {
+        _impls_emit!(@$m and
+            _impls_emit!(@$m not _impls!(@$m $type: $t1 < $($t1_generic),+ >)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // XOR: 0 lifetimes, 0 generics.
+    (@$m:ident $type:ty: $(! !)* $t1:ident ^ $($t2:tt)+) => {
+        _impls_emit!(@$m xor
+            _impls!(@$m $type: $t1),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+    // XOR+NOT: 0 lifetimes, 0 generics.
+    (@$m:ident $type:ty: $(! !)* !$t1:ident ^ $($t2:tt)+) => {
+        _impls_emit!(@$m xor
+            _impls_emit!(@$m not _impls!(@$m $type: $t1)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // XOR: 1+ lifetimes, 0+ generics.
+    (
+        @$m:ident $type:ty: $(! !)*
+        $t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
+        ^
+        $($t2:tt)+
+    ) => {
+        _impls_emit!(@$m xor
+            _impls!(@$m $type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
     // XOR+NOT: 1+ lifetimes, 0+ generics.
     (
-        $type:ty: $(! !)*
+        @$m:ident $type:ty: $(! !)*
         ! $t1:ident < $($t1_lifetime:lifetime),+ $(, $t1_generic:ty)* $(,)? >
         ^
         $($t2:tt)+
-    ) => {{
-        !_impls!($type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >)
-        ^
-        _impls!($type: $($t2)+)
-    }};
+    ) => {
+        _impls_emit!(@$m xor
+            _impls_emit!(@$m not
+                _impls!(@$m $type: $t1 < $($t1_lifetime),+ $(, $t1_generic)* >)
+            ),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
 
     // XOR: 0 lifetimes, 1+ generics.
     (
-        $type:ty: $(! !)*
+        @$m:ident $type:ty: $(! !)*
         $t1:ident < $($t1_generic:ty),+ $(,)? >
         ^
         $($t2:tt)+
-    ) => {{
-        _impls!($type: $t1 < $($t1_generic),+ >)
-        ^
-        _impls!($type: $($t2)+)
-    }};
+    ) => {
+        _impls_emit!(@$m xor
+            _impls!(@$m $type: $t1 < $($t1_generic),+ >),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
     // XOR+NOT: 0 lifetimes, 1+ generics.
     (
-        $type:ty: $(! !)*
+        @$m:ident $type:ty: $(! !)*
         ! $t1:ident < $($t1_generic:ty),+ $(,)? >
         ^
         $($t2:tt)+
+    ) => {
+        _impls_emit!(@$m xor
+            _impls_emit!(@$m not _impls!(@$m $type: $t1 < $($t1_generic),+ >)),
+            _impls!(@$m $type: $($t2)+)
+        )
+    };
+
+    // DEFAULT: Evaluate to a `bool`.
+    ($type:ty: $($trait_expr:tt)+) => {
+        _impls!(@bool $type: $($trait_expr)+)
+    };
+}
+
+/// Evaluates `impls!(for<...> ...)` over witness types that implement exactly
+/// the given bounds.
+///
+/// The fields of each witness opt out of every auto trait, so that only listed
+/// auto traits are implemented. Methods are never called, since the type is
+/// only used for trait resolution.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_witness {
+    // Collect the parameters up to the closing `>`, splitting `>>` so that
+    // bounds such as `PartialEq<u8>>` parse.
+    (@for [$($params:tt)*] [] > $($rest:tt)+) => {
+        _impls_witness!(@eval [$($params)*] $($rest)+)
+    };
+    (@for $params:tt $depth:tt >> $($rest:tt)+) => {
+        _impls_witness!(@for $params $depth > > $($rest)+)
+    };
+    (@for [$($params:tt)*] [$d:tt $($depth:tt)*] > $($rest:tt)+) => {
+        _impls_witness!(@for [$($params)* >] [$($depth)*] $($rest)+)
+    };
+    (@for [$($params:tt)*] [$($depth:tt)*] < $($rest:tt)+) => {
+        _impls_witness!(@for [$($params)* <] [< $($depth)*] $($rest)+)
+    };
+    (@for [$($params:tt)*] $depth:tt $next:tt $($rest:tt)+) => {
+        _impls_witness!(@for [$($params)* $next] $depth $($rest)+)
+    };
+
+    (
+        @eval [$(
+            $param:ident $(:
+                $bound:ident $(<$($arg:ty),+>)?
+                $(+ $bounds:ident $(<$($args:ty),+>)?)*
+            )?
+        ),+ $(,)?]
+        $type:ty: $($trait_expr:tt)+
     ) => {{
-        ! _impls!($type: $t1 < $($t1_generic),+ >)
-        ^
-        _impls!($type: $($t2)+)
+        $(_impls_witness!(@type impl $param $(
+            [$bound $(<$($arg),+>)?]
+            $([$bounds $(<$($args),+>)?])*
+        )?);)+
+        _impls!(@bool $type: $($trait_expr)+)
+    }};
+
+    // The type parameter lets lenient impls have bounds on the witness that
+    // are not trivially false.
+    (@type $mode:tt $param:ident $([$($bound:tt)+])*) => {
+        #[allow(dead_code)]
+        struct $param<__T = ()>(
+            $crate::_core::marker::PhantomData<*const ()>,
+            $crate::_core::marker::PhantomData<
+                &'static mut $crate::_core::cell::UnsafeCell<()>,
+            >,
+            $crate::_core::marker::PhantomPinned,
+            $crate::_core::marker::PhantomData<__T>,
+        );
+        $(_impls_witness!(@$mode $param $($bound)+);)*
+    };
+
+    (@impl $param:ident Send) => {
+        unsafe impl $crate::_core::marker::Send for $param {}
+    };
+    (@impl $param:ident Sync) => {
+        unsafe impl $crate::_core::marker::Sync for $param {}
+    };
+    (@impl $param:ident Copy) => {
+        impl $crate::_core::marker::Copy for $param {}
+    };
+    (@impl $param:ident Clone) => {
+        #[allow(clippy::all)]
+        impl $crate::_core::clone::Clone for $param {
+            fn clone(&self) -> Self {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident Default) => {
+        impl $crate::_core::default::Default for $param {
+            fn default() -> Self {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident Debug) => {
+        impl $crate::_core::fmt::Debug for $param {
+            fn fmt(
+                &self,
+                _: &mut $crate::_core::fmt::Formatter,
+            ) -> $crate::_core::fmt::Result {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident Display) => {
+        impl $crate::_core::fmt::Display for $param {
+            fn fmt(
+                &self,
+                _: &mut $crate::_core::fmt::Formatter,
+            ) -> $crate::_core::fmt::Result {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident PartialEq) => {
+        _impls_witness!(@impl $param PartialEq<$param>);
+    };
+    (@impl $param:ident PartialEq<$rhs:ty>) => {
+        impl $crate::_core::cmp::PartialEq<$rhs> for $param {
+            fn eq(&self, _: &$rhs) -> bool {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident Eq) => {
+        impl $crate::_core::cmp::Eq for $param {}
+    };
+    (@impl $param:ident PartialOrd) => {
+        _impls_witness!(@impl $param PartialOrd<$param>);
+    };
+    (@impl $param:ident PartialOrd<$rhs:ty>) => {
+        #[allow(clippy::all)]
+        impl $crate::_core::cmp::PartialOrd<$rhs> for $param {
+            fn partial_cmp(
+                &self,
+                _: &$rhs,
+            ) -> $crate::_core::option::Option<$crate::_core::cmp::Ordering> {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident Ord) => {
+        impl $crate::_core::cmp::Ord for $param {
+            fn cmp(&self, _: &Self) -> $crate::_core::cmp::Ordering {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@impl $param:ident Hash) => {
+        impl $crate::_core::hash::Hash for $param {
+            fn hash<H: $crate::_core::hash::Hasher>(&self, _: &mut H) {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    // Any other trait, such as `Unpin` or a marker trait, as written.
+    (@impl $param:ident $bound:ident $(<$($arg:ty),+>)?) => {
+        impl $bound $(<$($arg),+>)? for $param {}
+    };
+
+    // Subtraits are only implemented if their supertraits are, so that every
+    // subset of candidates in `infer_bounds` compiles.
+    (@lenient $param:ident Copy) => {
+        impl<__T> $crate::_core::marker::Copy for $param<__T>
+        where
+            $param<__T>: $crate::_core::clone::Clone,
+        {
+        }
+    };
+    (@lenient $param:ident Eq) => {
+        impl<__T> $crate::_core::cmp::Eq for $param<__T>
+        where
+            $param<__T>: $crate::_core::cmp::PartialEq,
+        {
+        }
+    };
+    (@lenient $param:ident PartialOrd) => {
+        _impls_witness!(@lenient $param PartialOrd<$param>);
+    };
+    (@lenient $param:ident PartialOrd<$rhs:ty>) => {
+        #[allow(clippy::all)]
+        impl<__T> $crate::_core::cmp::PartialOrd<$rhs> for $param<__T>
+        where
+            $param<__T>: $crate::_core::cmp::PartialEq<$rhs>,
+        {
+            fn partial_cmp(
+                &self,
+                _: &$rhs,
+            ) -> $crate::_core::option::Option<$crate::_core::cmp::Ordering> {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@lenient $param:ident Ord) => {
+        impl<__T> $crate::_core::cmp::Ord for $param<__T>
+        where
+            $param<__T>: $crate::_core::cmp::Eq
+                + $crate::_core::cmp::PartialOrd,
+        {
+            fn cmp(&self, _: &Self) -> $crate::_core::cmp::Ordering {
+                $crate::_core::unreachable!()
+            }
+        }
+    };
+    (@lenient $param:ident $($bound:tt)+) => {
+        _impls_witness!(@impl $param $($bound)+);
+    };
+}
+
+/// Splits comma-separated logical trait expressions into parenthesized groups
+/// and passes them to the `$cb` macro after `$args`.
+///
+/// Commas within angle brackets, such as in `Trait<A, B>`, do not split.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_split {
+    ($cb:ident [$($args:tt)*] $($input:tt)*) => {
+        _impls_split!(@go $cb [$($args)*] [] [] [] $($input)*)
+    };
+
+    // Finished.
+    (@go $cb:ident [$($args:tt)*] [$($done:tt)*] [] []) => {
+        $cb!($($args)* [$($done)*])
+    };
+    (@go $cb:ident $args:tt [$($done:tt)*] [$($cur:tt)+] []) => {
+        _impls_split!(@go $cb $args [$($done)* ($($cur)+)] [] [])
+    };
+
+    // Split at top-level commas.
+    (
+        @go $cb:ident $args:tt [$($done:tt)*] [$($cur:tt)+] []
+        , $($rest:tt)*
+    ) => {
+        _impls_split!(@go $cb $args [$($done)* ($($cur)+)] [] [] $($rest)*)
+    };
+    (@go $cb:ident $args:tt $done:tt [] [] , $($rest:tt)*) => {
+        _impls_split!(@go $cb $args $done [] [] $($rest)*)
+    };
+
+    // Classify the next token while keeping it as written.
+    (
+        @go $cb:ident $args:tt $done:tt $cur:tt $depth:tt
+        $next:tt $($rest:tt)*
+    ) => {
+        _impls_split!(@tok [$next] $next $cb $args $done $cur $depth $($rest)*)
+    };
+
+    // Track angle bracket depth.
+    (
+        @tok [$t:tt] < $cb:ident $args:tt $done:tt [$($cur:tt)*]
+        [$($depth:tt)*] $($rest:tt)*
+    ) => {
+        _impls_split!(
+            @go $cb $args $done [$($cur)* $t] [< $($depth)*] $($rest)*
+        )
+    };
+    (
+        @tok [$t:tt] > $cb:ident $args:tt $done:tt [$($cur:tt)*]
+        [$d:tt $($depth:tt)*] $($rest:tt)*
+    ) => {
+        _impls_split!(@go $cb $args $done [$($cur)* $t] [$($depth)*] $($rest)*)
+    };
+    (
+        @tok [$t:tt] >> $cb:ident $args:tt $done:tt [$($cur:tt)*]
+        [$d1:tt $d2:tt $($depth:tt)*] $($rest:tt)*
+    ) => {
+        _impls_split!(@go $cb $args $done [$($cur)* $t] [$($depth)*] $($rest)*)
+    };
+    (
+        @tok [$t:tt] $_t:tt $cb:ident $args:tt $done:tt [$($cur:tt)*]
+        $depth:tt $($rest:tt)*
+    ) => {
+        _impls_split!(@go $cb $args $done [$($cur)* $t] $depth $($rest)*)
+    };
+}
+
+/// Emits the output of `_impls` for a given mode.
+///
+/// - `@bool`: a `bool` expression.
+/// - `@node`: an [`explain::Node`](explain/enum.Node.html) expression.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_emit {
+    (@bool leaf $type:ty: $trait:path) => {{
+        // Do not import types in order to prevent trait name collisions.
+
+        /// Fallback trait with `False` for `IMPLS` if the type does not
+        /// implement the given trait.
+        trait DoesNotImpl {
+            const IMPLS: bool = false;
+        }
+        impl<T: ?Sized> DoesNotImpl for T {}
+
+        /// Concrete type with `True` for `IMPLS` if the type implements the
+        /// given trait. Otherwise, it falls back to `DoesNotImpl`.
+        struct Wrapper<T: ?Sized>($crate::_core::marker::PhantomData<T>);
+
+        #[allow(dead_code)]
+        impl<T: ?Sized + $trait> Wrapper<T> {
+            const IMPLS: bool = true;
+        }
+
+        <Wrapper<$type>>::IMPLS
     }};
+    (@bool not $a:expr) => {
+        !$a
+    };
+    (@bool or $a:expr, $b:expr) => {
+        $a | $b
+    };
+    (@bool and $a:expr, $b:expr) => {
+        $a & $b
+    };
+    (@bool xor $a:expr, $b:expr) => {
+        $a ^ $b
+    };
+
+    (@node leaf $type:ty: $trait:path) => {
+        $crate::explain::Node::Leaf($crate::explain::Leaf {
+            name: _impls_stringify!($trait),
+            value: _impls_emit!(@bool leaf $type: $trait),
+        })
+    };
+    (@node not $a:expr) => {
+        $crate::explain::Node::Not(&$a)
+    };
+    (@node or $a:expr, $b:expr) => {
+        $crate::explain::Node::Or(&$a, &$b)
+    };
+    (@node and $a:expr, $b:expr) => {
+        $crate::explain::Node::And(&$a, &$b)
+    };
+    (@node xor $a:expr, $b:expr) => {
+        $crate::explain::Node::Xor(&$a, &$b)
+    };
+}
+
+// Built-in macros cannot be called by path before Rust 1.38, and calls within
+// `local_inner_macros` macros are resolved in this crate, so these forward to
+// them from the caller's crate.
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impls_stringify {
+    ($($tokens:tt)*) => {
+        stringify!($($tokens)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impls_concat {
+    ($($args:tt)*) => {
+        concat!($($args)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impls_cfg {
+    ($($spec:tt)*) => {
+        cfg!($($spec)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impls_compile_error {
+    ($($message:tt)*) => {
+        compile_error!($($message)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impls_env {
+    ($($args:tt)*) => {
+        env!($($args)*)
+    };
 }
 
 // Declare after macros in order to be able to use them.
@@ -0,0 +1,201 @@
+//! Type-level booleans and logic.
+//!
+//! Use [`impls_ty!`] to get [`True`] or [`False`] from a logical trait
+//! expression. These can then be combined with [`Not`], [`And`], [`Or`], and
+//! [`Xor`], used to pick a type with [`If`], or required in a `where` clause
+//! with [`IsTrue`].
+//!
+//! Like [`impls!`], the expression is evaluated in a constant, so the type
+//! must not depend on generic parameters.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[macro_use] extern crate impls;
+//! use impls::logic::{Bool, If, IsTrue};
+//! use std::rc::Rc;
+//!
+//! type IsShared = impls_ty!(Rc<u8>: Clone & !Send);
+//! assert!(IsShared::VALUE);
+//!
+//! type Handle = <IsShared as If<Rc<u8>, Box<u8>>>::Output;
+//! let handle: Handle = Rc::new(1);
+//!
+//! // Fails to compile if `String` stops being `Send`.
+//! fn send_names(names: Vec<String>)
+//! where
+//!     impls_ty!(String: Send): IsTrue,
+//! {
+//!     std::thread::spawn(move || drop(names));
+//! }
+//! ```
+//!
+//! [`impls_ty!`]: ../macro.impls_ty.html
+//! [`impls!`]: ../macro.impls.html
+//! [`True`]: struct.True.html
+//! [`False`]: struct.False.html
+//! [`Not`]: trait.Not.html
+//! [`And`]: trait.And.html
+//! [`Or`]: trait.Or.html
+//! [`Xor`]: trait.Xor.html
+//! [`If`]: trait.If.html
+//! [`IsTrue`]: trait.IsTrue.html
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::True {}
+    impl Sealed for super::False {}
+}
+
+/// A type-level boolean, which is either [`True`] or [`False`].
+///
+/// This trait is sealed and cannot be implemented for other types.
+///
+/// [`True`]: struct.True.html
+/// [`False`]: struct.False.html
+pub trait Bool: sealed::Sealed {
+    /// The value as a `bool`.
+    const VALUE: bool;
+}
+
+/// The type-level `true`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct True;
+
+/// The type-level `false`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct False;
+
+impl True {
+    /// Returns `true`.
+    pub const fn value(self) -> bool {
+        true
+    }
+}
+
+impl False {
+    /// Returns `false`.
+    pub const fn value(self) -> bool {
+        false
+    }
+}
+
+impl Bool for True {
+    const VALUE: bool = true;
+}
+
+impl Bool for False {
+    const VALUE: bool = false;
+}
+
+/// Type-level `!`.
+pub trait Not: Bool {
+    /// The negation of `Self`.
+    type Output: Bool;
+}
+
+impl Not for True {
+    type Output = False;
+}
+
+impl Not for False {
+    type Output = True;
+}
+
+/// Type-level `&`.
+pub trait And<B: Bool>: Bool {
+    /// Whether both `Self` and `B` are `True`.
+    type Output: Bool;
+}
+
+impl<B: Bool> And<B> for True {
+    type Output = B;
+}
+
+impl<B: Bool> And<B> for False {
+    type Output = False;
+}
+
+/// Type-level `|`.
+pub trait Or<B: Bool>: Bool {
+    /// Whether either `Self` or `B` is `True`.
+    type Output: Bool;
+}
+
+impl<B: Bool> Or<B> for True {
+    type Output = True;
+}
+
+impl<B: Bool> Or<B> for False {
+    type Output = B;
+}
+
+/// Type-level `^`.
+pub trait Xor<B: Bool>: Bool {
+    /// Whether exactly one of `Self` and `B` is `True`.
+    type Output: Bool;
+}
+
+impl<B: Not> Xor<B> for True {
+    type Output = B::Output;
+}
+
+impl<B: Bool> Xor<B> for False {
+    type Output = B;
+}
+
+/// Type-level `if`, which selects `Then` for `True` and `Else` for `False`.
+pub trait If<Then: ?Sized, Else: ?Sized>: Bool {
+    /// The selected type.
+    type Output: ?Sized;
+}
+
+impl<Then: ?Sized, Else: ?Sized> If<Then, Else> for True {
+    type Output = Then;
+}
+
+impl<Then: ?Sized, Else: ?Sized> If<Then, Else> for False {
+    type Output = Else;
+}
+
+/// A bound satisfied only by [`True`].
+///
+/// [`True`]: struct.True.html
+pub trait IsTrue: Bool {}
+
+impl IsTrue for True {}
+
+/// Expands to [`True`] if a type implements a logical trait expression, or
+/// [`False`] otherwise.
+///
+/// This is the type-level counterpart of [`impls!`], with the same grammar.
+/// See the [`logic`] module for how to use the result.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use impls::logic::{And, Bool, False, True};
+///
+/// let _: impls_ty!(u8: Copy & Send) = True;
+/// let _: impls_ty!(String: Copy) = False;
+///
+/// type Both = <impls_ty!(u8: Send) as And<impls_ty!(u8: Sync)>>::Output;
+/// assert!(Both::VALUE);
+/// ```
+///
+/// [`True`]: logic/struct.True.html
+/// [`False`]: logic/struct.False.html
+/// [`impls!`]: macro.impls.html
+/// [`logic`]: logic/index.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_ty {
+    ($type:ty: $($trait_expr:tt)+) => {
+        impls_type_if!(
+            $type: $($trait_expr)+
+                => $crate::logic::True,
+                else => $crate::logic::False
+        )
+    };
+}
@@ -0,0 +1,127 @@
+//! Traits reified as zero-sized marker types and values.
+//!
+//! Use [`trait_marker!`] to define a marker.
+//!
+//! [`trait_marker!`]: ../macro.trait_marker.html
+
+use _core::{fmt, marker::PhantomData};
+
+/// A zero-sized value that stands for the marker trait `M`, defined by
+/// [`trait_marker!`].
+///
+/// `M` is the trait object type of the marker trait, such as `dyn CloneM`.
+///
+/// A `Marker` names its trait and bounds, but cannot itself check whether a
+/// type implements them. Like [`impls!`], a check needs the concrete type and
+/// the trait at the call site, so it is written with the marker trait, as in
+/// `impls!(MyType: CloneM)`.
+///
+/// [`trait_marker!`]: ../macro.trait_marker.html
+/// [`impls!`]: ../macro.impls.html
+pub struct Marker<M: ?Sized>(PhantomData<M>);
+
+/// The bounds of a marker trait, implemented by [`trait_marker!`] for its
+/// trait object type.
+///
+/// [`trait_marker!`]: ../macro.trait_marker.html
+#[doc(hidden)]
+pub trait _Bounds {
+    const BOUNDS: &'static str;
+}
+
+impl<M: ?Sized> Marker<M> {
+    #[doc(hidden)]
+    pub const fn _new() -> Self {
+        Marker(PhantomData)
+    }
+}
+
+impl<M: ?Sized + _Bounds> Marker<M> {
+    /// Returns the bounds of the marker as written, such as `Send + Sync`.
+    pub fn bounds(&self) -> &'static str {
+        M::BOUNDS
+    }
+}
+
+// These are implemented manually to not require `M: Clone` or `M: Debug`.
+
+impl<M: ?Sized> Clone for Marker<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: ?Sized> Copy for Marker<M> {}
+
+impl<M: ?Sized + _Bounds> fmt::Debug for Marker<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Marker").field(&M::BOUNDS).finish()
+    }
+}
+
+/// Defines a trait that stands for the bounds of another, along with a
+/// zero-sized [`Marker`] constant of the same name.
+///
+/// `trait_marker!(pub CloneM = Clone)` defines:
+///
+/// - The trait `CloneM`, which is implemented for every type that implements
+///   `Clone`. It can be used anywhere a trait can, including in [`impls!`],
+///   [`impls_matrix!`], and [`impls_snapshot!`], and combined with other
+///   traits in logical trait expressions.
+/// - The constant `CloneM` of type `Marker<dyn CloneM>`, which names the
+///   trait and its bounds as a value or a type.
+///
+/// Types are only checked against a marker by writing the marker trait in a
+/// macro such as [`impls!`] or [`impls_mask!`]. A set of predicates can be
+/// defined once as markers and listed in each check, but not stored as
+/// `Marker` values and checked later, since a check needs the concrete type.
+///
+/// The bounds can be any that are valid in a `where` clause, such as
+/// `Send + Sync` or `PartialEq<u8>`. Attributes, including doc comments, are
+/// applied to both the trait and the constant.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+///
+/// trait_marker!(pub CloneM = Clone);
+/// trait_marker!(pub ThreadSafe = Send + Sync);
+///
+/// assert!(impls!(Rc<u8>: CloneM & !ThreadSafe));
+/// assert!(impls!(String: CloneM & ThreadSafe));
+///
+/// let matrix = impls_matrix!([u8, Rc<u8>]: [CloneM, ThreadSafe]);
+/// assert_eq!(matrix.get(1, 1), Some(false));
+///
+/// assert_eq!(impls_mask!(Rc<u8>: [CloneM, ThreadSafe]), 0b01);
+///
+/// assert_eq!(ThreadSafe.bounds(), "Send + Sync");
+/// assert_eq!(std::mem::size_of_val(&ThreadSafe), 0);
+/// ```
+///
+/// [`Marker`]: marker/struct.Marker.html
+/// [`impls!`]: macro.impls.html
+/// [`impls_mask!`]: macro.impls_mask.html
+/// [`impls_matrix!`]: macro.impls_matrix.html
+/// [`impls_snapshot!`]: macro.impls_snapshot.html
+#[macro_export(local_inner_macros)]
+macro_rules! trait_marker {
+    ($(#[$attr:meta])* $vis:vis $name:ident = $($bounds:tt)+) => {
+        $(#[$attr])*
+        $vis trait $name {}
+
+        impl<T: ?Sized> $name for T where T: $($bounds)+ {}
+
+        impl $crate::marker::_Bounds for dyn $name {
+            const BOUNDS: &'static str =
+                _impls_stringify!($($bounds)+);
+        }
+
+        $(#[$attr])*
+        #[allow(non_upper_case_globals)]
+        $vis const $name: $crate::marker::Marker<dyn $name> =
+            $crate::marker::Marker::_new();
+    };
+}
@@ -0,0 +1,526 @@
+//! Tables of which types implement which logical trait expressions.
+//!
+//! Use [`impls_matrix!`] to create a [`Matrix`].
+//!
+//! [`impls_matrix!`]: ../macro.impls_matrix.html
+//! [`Matrix`]: struct.Matrix.html
+
+use _core::fmt;
+
+/// A table of `bool`s for types (rows) over logical trait expressions
+/// (columns), returned by [`impls_matrix!`].
+///
+/// This can be rendered as:
+/// - Plain text via [`text`](#method.text).
+/// - GitHub Markdown via [`markdown`](#method.markdown).
+/// - JSON via [`json`](#method.json).
+///
+/// [`impls_matrix!`]: ../macro.impls_matrix.html
+#[derive(Clone, Copy, Debug)]
+pub struct Matrix<'a> {
+    /// The types as written.
+    pub rows: &'a [&'static str],
+    /// The logical trait expressions as written.
+    pub columns: &'a [&'static str],
+    /// Whether the type of each row implements the expression of each column.
+    pub cells: &'a [&'a [bool]],
+}
+
+impl<'a> Matrix<'a> {
+    /// Returns whether the type at `row` implements the expression at
+    /// `column`, or `None` if either is out of bounds.
+    pub fn get(&self, row: usize, column: usize) -> Option<bool> {
+        self.cells.get(row)?.get(column).cloned()
+    }
+
+    /// Returns a renderer of this matrix as an aligned plain text table.
+    pub fn text(&self) -> Text<'a> {
+        Text(*self)
+    }
+
+    /// Returns a renderer of this matrix as a GitHub Markdown table.
+    pub fn markdown(&self) -> Markdown<'a> {
+        Markdown(*self)
+    }
+
+    /// Returns a renderer of this matrix as a JSON object that maps each type
+    /// to an object of each expression to its value.
+    pub fn json(&self) -> Json<'a> {
+        Json(*self)
+    }
+}
+
+/// Returns the text of a cell.
+fn cell(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// A stringified type or expression, written on a single line.
+///
+/// Long token streams are broken into multiple lines by `stringify!`, which
+/// would otherwise break rows in tables.
+pub(crate) struct Label<'a>(pub(crate) &'a str);
+
+impl<'a> Label<'a> {
+    /// Returns the characters written, with whitespace collapsed into single
+    /// spaces.
+    fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        self.0.split_whitespace().enumerate().flat_map(|(i, word)| {
+            let space = if i == 0 { "" } else { " " };
+            space.chars().chain(word.chars())
+        })
+    }
+
+    /// Returns the number of characters written.
+    fn width(&self) -> usize {
+        self.chars().count()
+    }
+
+    /// Writes the label followed by spaces to fill `width`.
+    fn pad(&self, f: &mut fmt::Formatter, width: usize) -> fmt::Result {
+        write!(f, "{}", self)?;
+        repeat(f, " ", width.saturating_sub(self.width()))
+    }
+}
+
+impl<'a> fmt::Display for Label<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.chars().try_for_each(|c| write!(f, "{}", c))
+    }
+}
+
+/// Writes `s` as a double-quoted string, as used by both JSON and DOT.
+fn quoted(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in Label(s).chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Writes `n` copies of `s`.
+fn repeat(f: &mut fmt::Formatter, s: &str, n: usize) -> fmt::Result {
+    for _ in 0..n {
+        f.write_str(s)?;
+    }
+    Ok(())
+}
+
+/// Renders a [`Matrix`](struct.Matrix.html) as an aligned plain text table.
+#[derive(Clone, Copy, Debug)]
+pub struct Text<'a>(Matrix<'a>);
+
+impl<'a> fmt::Display for Text<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let matrix = &self.0;
+        let rows_width = matrix.rows.iter().map(|r| Label(r).width()).max();
+        let rows_width = rows_width.unwrap_or(0);
+        let width = |column: &str| Label(column).width().max(3);
+        let last = matrix.columns.len().saturating_sub(1);
+
+        repeat(f, " ", rows_width)?;
+        for (i, column) in matrix.columns.iter().enumerate() {
+            f.write_str(" | ")?;
+            Label(column).pad(f, if i == last { 0 } else { width(column) })?;
+        }
+
+        f.write_str("\n")?;
+        repeat(f, "-", rows_width)?;
+        for column in matrix.columns {
+            f.write_str("-+-")?;
+            repeat(f, "-", width(column))?;
+        }
+
+        for (row, values) in matrix.rows.iter().zip(matrix.cells) {
+            f.write_str("\n")?;
+            Label(row).pad(f, rows_width)?;
+            for (i, (column, &value)) in
+                matrix.columns.iter().zip(*values).enumerate()
+            {
+                f.write_str(" | ")?;
+                let width = if i == last { 0 } else { width(column) };
+                Label(cell(value)).pad(f, width)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a [`Matrix`](struct.Matrix.html) as a GitHub Markdown table.
+#[derive(Clone, Copy, Debug)]
+pub struct Markdown<'a>(Matrix<'a>);
+
+impl<'a> Markdown<'a> {
+    /// Writes `s` as inline code within a table cell.
+    fn code(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+        f.write_str("`")?;
+        for c in Label(s).chars() {
+            // Pipes end cells, even within inline code.
+            if c == '|' {
+                f.write_str("\\")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        f.write_str("`")
+    }
+}
+
+impl<'a> fmt::Display for Markdown<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let matrix = &self.0;
+
+        f.write_str("| Type |")?;
+        for column in matrix.columns {
+            f.write_str(" ")?;
+            Self::code(f, column)?;
+            f.write_str(" |")?;
+        }
+
+        f.write_str("\n| --- |")?;
+        for _ in matrix.columns {
+            f.write_str(" :-: |")?;
+        }
+
+        for (row, values) in matrix.rows.iter().zip(matrix.cells) {
+            f.write_str("\n| ")?;
+            Self::code(f, row)?;
+            f.write_str(" |")?;
+            for &value in values.iter() {
+                write!(f, " {} |", cell(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a [`Matrix`](struct.Matrix.html) as a JSON object.
+#[derive(Clone, Copy, Debug)]
+pub struct Json<'a>(Matrix<'a>);
+
+impl<'a> fmt::Display for Json<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let matrix = &self.0;
+
+        f.write_str("{")?;
+        for (i, (row, values)) in
+            matrix.rows.iter().zip(matrix.cells).enumerate()
+        {
+            f.write_str(if i == 0 { "\n  " } else { ",\n  " })?;
+            quoted(f, row)?;
+            f.write_str(": {")?;
+
+            for (j, (column, &value)) in
+                matrix.columns.iter().zip(*values).enumerate()
+            {
+                f.write_str(if j == 0 { "\n    " } else { ",\n    " })?;
+                quoted(f, column)?;
+                write!(f, ": {}", value)?;
+            }
+            f.write_str(if values.is_empty() { "}" } else { "\n  }" })?;
+        }
+        f.write_str(if matrix.rows.is_empty() { "}" } else { "\n}" })
+    }
+}
+
+/// A generic trait evaluated over every ordered pair of types, returned by
+/// [`relation_matrix!`].
+///
+/// [`relation_matrix!`]: ../macro.relation_matrix.html
+#[derive(Clone, Copy, Debug)]
+pub struct Relation<'a> {
+    /// The trait as written, without generic arguments.
+    pub name: &'static str,
+    /// Whether the type of each row implements the trait over the type of
+    /// each column.
+    ///
+    /// Columns are labeled with the trait and its argument, such as
+    /// `From<u8>`.
+    pub matrix: Matrix<'a>,
+}
+
+impl<'a> Relation<'a> {
+    /// Returns the types as written.
+    pub fn types(&self) -> &'a [&'static str] {
+        self.matrix.rows
+    }
+
+    /// Returns whether the type at `a` implements the trait over the type at
+    /// `b`, or `None` if either is out of bounds.
+    pub fn get(&self, a: usize, b: usize) -> Option<bool> {
+        self.matrix.get(a, b)
+    }
+
+    /// Returns a renderer of this relation as a [Graphviz] DOT graph.
+    ///
+    /// Each type is a node, and there is an edge `A -> B` if `A` implements
+    /// the trait over `B`. Edges from a type to itself are omitted.
+    ///
+    /// [Graphviz]: https://graphviz.org
+    pub fn dot(&self) -> Dot<'a> {
+        Dot(*self)
+    }
+}
+
+/// Renders a [`Relation`](struct.Relation.html) as a Graphviz DOT graph.
+#[derive(Clone, Copy, Debug)]
+pub struct Dot<'a>(Relation<'a>);
+
+impl<'a> fmt::Display for Dot<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let relation = &self.0;
+        let types = relation.types();
+
+        f.write_str("digraph ")?;
+        quoted(f, relation.name)?;
+        f.write_str(" {")?;
+
+        for ty in types {
+            f.write_str("\n    ")?;
+            quoted(f, ty)?;
+            f.write_str(";")?;
+        }
+
+        for (i, a) in types.iter().enumerate() {
+            for (j, b) in types.iter().enumerate() {
+                if i != j && relation.get(i, j) == Some(true) {
+                    f.write_str("\n    ")?;
+                    quoted(f, a)?;
+                    f.write_str(" -> ")?;
+                    quoted(f, b)?;
+                    f.write_str(";")?;
+                }
+            }
+        }
+        f.write_str("\n}")
+    }
+}
+
+/// Returns a [`&'static Matrix`] of whether each type implements each logical
+/// trait expression.
+///
+/// Rows are types and columns are expressions, each labeled as written.
+/// Expressions are separated by commas. Commas within angle brackets, such as
+/// in `Trait<A, B>`, are part of the expression.
+///
+/// Writing `catalog` instead of a list of expressions uses every trait in the
+/// [catalog](catalog/index.html) as a column.
+///
+/// Because the matrix is a constant, types must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// use std::rc::Rc;
+///
+/// let matrix = impls_matrix!([String, Rc<u8>]: [Send, Clone ^ Copy]);
+///
+/// assert_eq!(matrix.get(1, 0), Some(false));
+/// println!("{}", matrix.text());
+///
+/// assert_eq!(matrix.markdown().to_string(), "\
+/// | Type | `Send` | `Clone ^ Copy` |
+/// | --- | :-: | :-: |
+/// | `String` | yes | yes |
+/// | `Rc<u8>` | no | yes |");
+///
+/// assert_eq!(matrix.json().to_string(), r#"{
+///   "String": {
+///     "Send": true,
+///     "Clone ^ Copy": true
+///   },
+///   "Rc<u8>": {
+///     "Send": false,
+///     "Clone ^ Copy": true
+///   }
+/// }"#);
+/// ```
+///
+/// The plain text table is printed as:
+///
+/// ```text
+///        | Send | Clone ^ Copy
+/// -------+------+-------------
+/// String | yes  | yes
+/// Rc<u8> | no   | yes
+/// ```
+///
+/// The result is a constant, so it can be used in `const` contexts:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// # use impls::matrix::Matrix;
+/// const MATRIX: &Matrix = impls_matrix!([u8, u16]: [From<u8>, Into<u8>]);
+/// ```
+///
+/// Columns from the catalog are labeled by trait name:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// let matrix = impls_matrix!([u8, str]: catalog);
+///
+/// let sized = matrix.columns.iter().position(|&c| c == "Sized").unwrap();
+/// assert_eq!(matrix.get(0, sized), Some(true));
+/// assert_eq!(matrix.get(1, sized), Some(false));
+/// ```
+///
+/// [`&'static Matrix`]: matrix/struct.Matrix.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_matrix {
+    ([$($type:ty),+ $(,)?]: catalog) => {
+        _impls_catalog!(_impls_matrix [@catalog [$($type),+]])
+    };
+    ([$($type:ty),+ $(,)?]: [$($trait_exprs:tt)*]) => {
+        _impls_split!(_impls_matrix [[$($type),+]] $($trait_exprs)*)
+    };
+}
+
+/// Builds the output of `impls_matrix` from split expressions.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_matrix {
+    ([$($type:ty),+] $trait_exprs:tt) => {{
+        const MATRIX: &$crate::matrix::Matrix<'static> =
+            &$crate::matrix::Matrix {
+                rows: &[$(_impls_stringify!($type)),+],
+                columns: _impls_matrix!(@columns $trait_exprs),
+                cells: &[$(_impls_matrix!(@row $type: $trait_exprs)),+],
+            };
+        MATRIX
+    }};
+    (@columns [$(($($trait_expr:tt)+))*]) => {
+        &[$(_impls_stringify!($($trait_expr)+)),*]
+    };
+    (@row $type:ty: [$(($($trait_expr:tt)+))*]) => {
+        &[$(_impls!(@bool $type: $($trait_expr)+)),*]
+    };
+    (@catalog [$($type:ty),+] $catalog:tt) => {{
+        const MATRIX: &$crate::matrix::Matrix<'static> =
+            &$crate::matrix::Matrix {
+                rows: &[$(_impls_stringify!($type)),+],
+                columns: _impls_matrix!(@catalog_columns $catalog),
+                cells: &[$(_impls_matrix!(@catalog_row $type: $catalog)),+],
+            };
+        MATRIX
+    }};
+    (@catalog_columns [$([$group:ident $name:expr => $($trait:tt)+])*]) => {
+        &[$($name),*]
+    };
+    (
+        @catalog_row $type:ty:
+        [$([$group:ident $name:expr => $($trait:tt)+])*]
+    ) => {
+        &[$(_impls!(@bool $type: $($trait)+)),*]
+    };
+}
+
+/// Returns a [`&'static Relation`] of whether each type implements a generic
+/// trait over each type, including itself.
+///
+/// The cell at row `A` and column `B` is `true` if `A: Trait<B>`. This can be
+/// rendered as a grid via its [`matrix`] or as a Graphviz DOT graph via
+/// [`dot`].
+///
+/// Because the relation is a constant, types must not depend on generic
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// #[derive(Clone, Copy)]
+/// struct MyId(u32);
+///
+/// impl From<u16> for MyId {
+///     fn from(id: u16) -> Self {
+///         MyId(id.into())
+///     }
+/// }
+///
+/// let relation = relation_matrix!(From, [u8, u16, MyId]);
+///
+/// assert_eq!(relation.get(1, 0), Some(true));  // u16: From<u8>
+/// assert_eq!(relation.get(2, 0), Some(false)); // MyId: From<u8>
+/// assert_eq!(relation.get(2, 1), Some(true));  // MyId: From<u16>
+///
+/// assert_eq!(relation.matrix.markdown().to_string(), "\
+/// | Type | `From<u8>` | `From<u16>` | `From<MyId>` |
+/// | --- | :-: | :-: | :-: |
+/// | `u8` | yes | no | no |
+/// | `u16` | yes | yes | no |
+/// | `MyId` | no | yes | yes |");
+///
+/// assert_eq!(relation.dot().to_string(), r#"digraph "From" {
+///     "u8";
+///     "u16";
+///     "MyId";
+///     "u16" -> "u8";
+///     "MyId" -> "u16";
+/// }"#);
+/// ```
+///
+/// [`&'static Relation`]: matrix/struct.Relation.html
+/// [`matrix`]: matrix/struct.Relation.html#structfield.matrix
+/// [`dot`]: matrix/struct.Relation.html#method.dot
+#[macro_export(local_inner_macros)]
+macro_rules! relation_matrix {
+    ($($input:tt)+) => {
+        _relation_matrix!(@trait [] $($input)+)
+    };
+}
+
+/// Builds the output of `relation_matrix`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _relation_matrix {
+    // Collect the trait's tokens as written, up to the list of types.
+    (@trait $trait:tt , [$($type:ty),+ $(,)?]) => {
+        _relation_matrix!($trait [$($type),+] [$($type),+])
+    };
+    (@trait [$($trait:tt)*] $next:tt $($rest:tt)+) => {
+        _relation_matrix!(@trait [$($trait)* $next] $($rest)+)
+    };
+
+    ($trait:tt [$($type:ty),+] $types:tt) => {{
+        const RELATION: &$crate::matrix::Relation<'static> =
+            &$crate::matrix::Relation {
+                name: _relation_matrix!(@name $trait),
+                matrix: $crate::matrix::Matrix {
+                    rows: &[$(_impls_stringify!($type)),+],
+                    columns: _relation_matrix!(@columns $trait $types),
+                    cells: &[$(_relation_matrix!(@row $trait $type; $types)),+],
+                },
+            };
+        RELATION
+    }};
+    (@name [$($trait:tt)+]) => {
+        _impls_stringify!($($trait)+)
+    };
+    (@columns $trait:tt [$($type:ty),+]) => {
+        &[$(_relation_matrix!(@column $trait $type)),+]
+    };
+    (@column [$($trait:tt)+] $type:ty) => {
+        _impls_concat!(
+            _impls_stringify!($($trait)+),
+            "<",
+            _impls_stringify!($type),
+            ">",
+        )
+    };
+    (@row $trait:tt $a:ty; [$($b:ty),+]) => {
+        &[$(_relation_matrix!(@cell $trait $a; $b)),+]
+    };
+    (@cell [$($trait:tt)+] $a:ty; $b:ty) => {
+        _impls!(@bool $a: $($trait)+ <$b>)
+    };
+}
@@ -0,0 +1,244 @@
+//! Formatting of values whose types may not implement `Debug` or `Display`.
+//!
+//! Use [`maybe_debug!`] and [`maybe_display!`] to create a [`MaybeDebug`] or
+//! [`MaybeDisplay`].
+//!
+//! [`maybe_debug!`]: ../macro.maybe_debug.html
+//! [`maybe_display!`]: ../macro.maybe_display.html
+//! [`MaybeDebug`]: struct.MaybeDebug.html
+//! [`MaybeDisplay`]: struct.MaybeDisplay.html
+
+use _core::fmt;
+
+/// The placeholder used by default, where `{}` is the name of the type.
+const PLACEHOLDER: &str = "<{}>";
+
+/// Formats a value with [`Debug`] if its type implements it, or a placeholder
+/// with the name of its type otherwise, returned by [`maybe_debug!`].
+///
+/// [`Debug`]: https://doc.rust-lang.org/core/fmt/trait.Debug.html
+/// [`maybe_debug!`]: ../macro.maybe_debug.html
+#[derive(Clone, Copy)]
+pub struct MaybeDebug<'a> {
+    value: Option<&'a dyn fmt::Debug>,
+    type_name: &'static str,
+    placeholder: &'a str,
+}
+
+/// Formats a value with [`Display`] if its type implements it, or a
+/// placeholder with the name of its type otherwise, returned by
+/// [`maybe_display!`].
+///
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+/// [`maybe_display!`]: ../macro.maybe_display.html
+#[derive(Clone, Copy)]
+pub struct MaybeDisplay<'a> {
+    value: Option<&'a dyn fmt::Display>,
+    type_name: &'static str,
+    placeholder: &'a str,
+}
+
+impl<'a> MaybeDebug<'a> {
+    #[doc(hidden)]
+    pub fn _new(
+        value: Option<&'a dyn fmt::Debug>,
+        type_name: &'static str,
+    ) -> Self {
+        MaybeDebug {
+            value,
+            type_name,
+            placeholder: PLACEHOLDER,
+        }
+    }
+
+    /// Returns whether the type of the value implements `Debug`.
+    pub fn implements(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Returns the name of the type of the value, or `_` if the `type_name`
+    /// feature is disabled.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Sets the text written if the type does not implement `Debug`.
+    ///
+    /// Each `{}` in `placeholder` is replaced by the name of the type. The
+    /// default is `<{}>`.
+    pub fn with_placeholder(self, placeholder: &'a str) -> Self {
+        MaybeDebug {
+            placeholder,
+            ..self
+        }
+    }
+}
+
+impl<'a> MaybeDisplay<'a> {
+    #[doc(hidden)]
+    pub fn _new(
+        value: Option<&'a dyn fmt::Display>,
+        type_name: &'static str,
+    ) -> Self {
+        MaybeDisplay {
+            value,
+            type_name,
+            placeholder: PLACEHOLDER,
+        }
+    }
+
+    /// Returns whether the type of the value implements `Display`.
+    pub fn implements(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Returns the name of the type of the value, or `_` if the `type_name`
+    /// feature is disabled.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Sets the text written if the type does not implement `Display`.
+    ///
+    /// Each `{}` in `placeholder` is replaced by the name of the type. The
+    /// default is `<{}>`.
+    pub fn with_placeholder(self, placeholder: &'a str) -> Self {
+        MaybeDisplay {
+            placeholder,
+            ..self
+        }
+    }
+}
+
+impl<'a> fmt::Debug for MaybeDebug<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(value) => value.fmt(f),
+            None => placeholder(f, self.placeholder, self.type_name),
+        }
+    }
+}
+
+impl<'a> fmt::Display for MaybeDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(value) => value.fmt(f),
+            None => placeholder(f, self.placeholder, self.type_name),
+        }
+    }
+}
+
+/// Writes `placeholder` with each `{}` replaced by `type_name`.
+fn placeholder(
+    f: &mut fmt::Formatter,
+    placeholder: &str,
+    type_name: &str,
+) -> fmt::Result {
+    for (i, part) in placeholder.split("{}").enumerate() {
+        if i != 0 {
+            f.write_str(type_name)?;
+        }
+        f.write_str(part)?;
+    }
+    Ok(())
+}
+
+/// Returns a [`MaybeDebug`] that formats a value with `Debug` if its type
+/// implements it, or a placeholder with the name of its type otherwise.
+///
+/// The value is borrowed, as with [`format_args!`]. The placeholder defaults
+/// to `<{}>`, where `{}` is the name of the type, and can be changed with
+/// [`with_placeholder`].
+///
+/// The name of the type comes from [`type_name`] if the `type_name` feature is
+/// enabled, which requires Rust 1.38. Otherwise, it is written as `_`.
+///
+/// The value is formatted through [`try_as_dyn!`], so values whose types are
+/// generic parameters always format as the placeholder.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// struct Opaque;
+///
+/// assert_eq!(format!("{:?}", maybe_debug!("text")), "\"text\"");
+/// assert_eq!(format!("{:?}", maybe_debug!(Some(1))), "Some(1)");
+///
+/// let opaque = maybe_debug!(Opaque).with_placeholder("{} { .. }");
+/// assert!(format!("{:?}", opaque).ends_with(" { .. }"));
+/// ```
+///
+/// With the `type_name` feature, `opaque` is formatted as the path of
+/// `Opaque` followed by ` { .. }`. Without it, `opaque` is formatted as
+/// `_ { .. }`.
+///
+/// [`MaybeDebug`]: maybe/struct.MaybeDebug.html
+/// [`with_placeholder`]: maybe/struct.MaybeDebug.html#method.with_placeholder
+/// [`format_args!`]: https://doc.rust-lang.org/core/macro.format_args.html
+/// [`try_as_dyn!`]: macro.try_as_dyn.html
+/// [`type_name`]: https://doc.rust-lang.org/core/any/fn.type_name.html
+#[macro_export(local_inner_macros)]
+macro_rules! maybe_debug {
+    ($value:expr $(,)?) => {
+        _impls_maybe!(MaybeDebug, Debug, $value)
+    };
+}
+
+/// Returns a [`MaybeDisplay`] that formats a value with `Display` if its type
+/// implements it, or a placeholder with the name of its type otherwise.
+///
+/// See [`maybe_debug!`] for details.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// let values = (1.5, vec![1]);
+///
+/// assert_eq!(maybe_display!(values.0).to_string(), "1.5");
+/// assert_eq!(
+///     maybe_display!(values.1).with_placeholder("(vector)").to_string(),
+///     "(vector)",
+/// );
+/// ```
+///
+/// [`MaybeDisplay`]: maybe/struct.MaybeDisplay.html
+/// [`maybe_debug!`]: macro.maybe_debug.html
+#[macro_export(local_inner_macros)]
+macro_rules! maybe_display {
+    ($value:expr $(,)?) => {
+        _impls_maybe!(MaybeDisplay, Display, $value)
+    };
+}
+
+/// Builds the output of `maybe_debug` and `maybe_display`.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_maybe {
+    ($adapter:ident, $trait:ident, $value:expr) => {
+        // Matching keeps temporaries alive until the end of the statement.
+        match &$value {
+            __value => $crate::maybe::$adapter::_new(
+                try_as_dyn!(__value => dyn $crate::_core::fmt::$trait),
+                $crate::maybe::_type_name_of(__value),
+            ),
+        }
+    };
+}
+
+/// Returns the name of the type of a value if the `type_name` feature is
+/// enabled.
+#[cfg(feature = "type_name")]
+#[doc(hidden)]
+pub fn _type_name_of<T: ?Sized>(_: &T) -> &'static str {
+    _core::any::type_name::<T>()
+}
+
+/// Returns the name of the type of a value if the `type_name` feature is
+/// enabled.
+#[cfg(not(feature = "type_name"))]
+#[doc(hidden)]
+pub fn _type_name_of<T: ?Sized>(_: &T) -> &'static str {
+    "_"
+}
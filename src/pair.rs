@@ -0,0 +1,223 @@
+//! Checks that relational traits are implemented in both directions between
+//! two types.
+//!
+//! See [`symmetric!`], [`round_trip!`], and [`total_order_consistent!`].
+//!
+//! [`symmetric!`]: ../macro.symmetric.html
+//! [`round_trip!`]: ../macro.round_trip.html
+//! [`total_order_consistent!`]: ../macro.total_order_consistent.html
+
+use _core::fmt;
+
+/// A property of a pair of types that requires a set of trait bounds.
+///
+/// Its [`Display`] implementation lists every bound and marks the ones that
+/// are missing:
+///
+/// ```text
+/// `Foo` and `Bar` fail symmetry over `PartialEq`:
+///   Foo: PartialEq<Bar> = true
+///   Bar: PartialEq<Foo> = false (missing)
+/// ```
+///
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct Pair<'a> {
+    /// The property, such as "symmetry over `PartialEq`".
+    pub property: &'static str,
+    /// The first type as written.
+    pub a: &'static str,
+    /// The second type as written.
+    pub b: &'static str,
+    /// The bounds required for the property to hold.
+    pub bounds: &'a [Bound],
+}
+
+/// A trait bound on a type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bound {
+    /// The type as written.
+    pub ty: &'static str,
+    /// The trait as written, including generic arguments.
+    pub bound: &'static str,
+    /// Whether the type implements the trait.
+    pub value: bool,
+}
+
+impl<'a> Pair<'a> {
+    /// Returns `true` if every bound is implemented.
+    pub fn holds(&self) -> bool {
+        self.bounds.iter().all(|bound| bound.value)
+    }
+
+    /// Returns the bounds that are not implemented.
+    pub fn missing(&self) -> impl Iterator<Item = &'a Bound> {
+        self.bounds.iter().filter(|bound| !bound.value)
+    }
+}
+
+impl<'a> fmt::Display for Pair<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` and `{}` {} {}:",
+            self.a,
+            self.b,
+            if self.holds() { "satisfy" } else { "fail" },
+            self.property,
+        )?;
+        for bound in self.bounds {
+            write!(f, "\n  {}: {} = {}", bound.ty, bound.bound, bound.value)?;
+            if !bound.value {
+                f.write_str(" (missing)")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a [`&'static Pair`] that holds if each of two types implements a
+/// generic trait over the other.
+///
+/// This catches a `PartialEq<Foo> for Bar` implementation that is missing
+/// its mirror `PartialEq<Bar> for Foo`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// struct Foo;
+/// struct Bar;
+///
+/// impl PartialEq<Bar> for Foo {
+///     fn eq(&self, _: &Bar) -> bool { true }
+/// }
+///
+/// let pair = symmetric!(Foo, Bar: PartialEq);
+/// assert!(!pair.holds());
+///
+/// let missing: Vec<_> = pair.missing().map(|b| (b.ty, b.bound)).collect();
+/// assert_eq!(missing, [("Bar", "PartialEq<Foo>")]);
+///
+/// assert!(symmetric!(String, &'static str: PartialEq).holds());
+/// ```
+///
+/// In tests, the report can be used as the assertion message:
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// let pair = symmetric!(String, str: PartialEq);
+/// assert!(pair.holds(), "{}", pair);
+/// ```
+///
+/// [`&'static Pair`]: pair/struct.Pair.html
+#[macro_export(local_inner_macros)]
+macro_rules! symmetric {
+    ($a:ty, $b:ty: $($trait:tt)+) => {
+        _impls_pair!(@generic "symmetry over", $a, $b: $($trait)+)
+    };
+}
+
+/// Returns a [`&'static Pair`] that holds if values of each of two types can
+/// be converted into the other through a generic conversion trait.
+///
+/// This catches a `From<A> for B` implementation without `From<B> for A`.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// struct UserId(u64);
+///
+/// impl From<u64> for UserId {
+///     fn from(id: u64) -> Self { UserId(id) }
+/// }
+///
+/// let pair = round_trip!(UserId, u64: From);
+/// assert_eq!(pair.to_string(), "\
+/// `UserId` and `u64` fail round trip through `From`:
+///   UserId: From<u64> = true
+///   u64: From<UserId> = false (missing)");
+/// ```
+///
+/// [`&'static Pair`]: pair/struct.Pair.html
+#[macro_export(local_inner_macros)]
+macro_rules! round_trip {
+    ($a:ty, $b:ty: $($trait:tt)+) => {
+        _impls_pair!(@generic "round trip through", $a, $b: $($trait)+)
+    };
+}
+
+/// Returns a [`&'static Pair`] that holds if each of two types implements
+/// both [`PartialEq`] and [`PartialOrd`] over the other.
+///
+/// Comparisons that only work in one direction, or equality without
+/// ordering, make `a < b` and `b > a` disagree on whether they compile.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use] extern crate impls;
+/// assert!(total_order_consistent!(u8, u8).holds());
+///
+/// let pair = total_order_consistent!(String, &'static str);
+/// let missing: Vec<_> = pair.missing().map(|b| (b.ty, b.bound)).collect();
+/// assert_eq!(missing, [
+///     ("String", "PartialOrd<&'static str>"),
+///     ("&'static str", "PartialOrd<String>"),
+/// ]);
+/// ```
+///
+/// [`&'static Pair`]: pair/struct.Pair.html
+/// [`PartialEq`]: https://doc.rust-lang.org/core/cmp/trait.PartialEq.html
+/// [`PartialOrd`]: https://doc.rust-lang.org/core/cmp/trait.PartialOrd.html
+#[macro_export(local_inner_macros)]
+macro_rules! total_order_consistent {
+    ($a:ty, $b:ty) => {
+        _impls_pair!(
+            "consistent ordering",
+            $a, $b:
+            "PartialEq" => [$crate::_core::cmp::PartialEq],
+            "PartialOrd" => [$crate::_core::cmp::PartialOrd]
+        )
+    };
+}
+
+/// Builds a `Pair` from traits named `$name` that `$a` and `$b` must each
+/// implement over the other.
+#[doc(hidden)]
+#[macro_export(local_inner_macros)]
+macro_rules! _impls_pair {
+    // A generic trait named `$trait`, with `$label` before it in the
+    // property.
+    (@generic $label:tt, $a:ty, $b:ty: $($trait:tt)+) => {
+        _impls_pair!(
+            _impls_concat!($label, " `", _impls_stringify!($($trait)+), "`"),
+            $a, $b: _impls_stringify!($($trait)+) => [$($trait)+]
+        )
+    };
+    ($property:expr, $a:ty, $b:ty: $($name:expr => [$($trait:tt)+]),+) => {{
+        const PAIR: &$crate::pair::Pair<'static> = &$crate::pair::Pair {
+            property: $property,
+            a: _impls_stringify!($a),
+            b: _impls_stringify!($b),
+            bounds: &[$(
+                _impls_pair!(@bound $a: $name, [$($trait)+] $b),
+                _impls_pair!(@bound $b: $name, [$($trait)+] $a),
+            )+],
+        };
+        PAIR
+    }};
+    (@bound $type:ty: $name:expr, [$($trait:tt)+] $arg:ty) => {
+        $crate::pair::Bound {
+            ty: _impls_stringify!($type),
+            bound: _impls_concat!(
+                $name,
+                "<",
+                _impls_stringify!($arg),
+                ">",
+            ),
+            value: _impls!(@bool $type: $($trait)+ <$arg>),
+        }
+    };
+}
@@ -0,0 +1,326 @@
+//! Golden-file snapshots of which traits types implement.
+//!
+//! Use [`impls_snapshot!`] to compare a [`Matrix`] against a file checked into
+//! the repository. This module requires the `std` feature.
+//!
+//! A snapshot lists each type followed by its traits:
+//!
+//! ```text
+//! String
+//!   Send: yes
+//!   Copy: no
+//! Rc<u8>
+//!   Send: no
+//!   Copy: no
+//! ```
+//!
+//! [`impls_snapshot!`]: ../macro.impls_snapshot.html
+//! [`Matrix`]: ../matrix/struct.Matrix.html
+
+use _std::error;
+use _std::fmt;
+use _std::fs;
+use _std::io;
+use _std::path::{Path, PathBuf};
+use _std::string::{String, ToString};
+use _std::vec::Vec;
+
+use matrix::{Label, Matrix};
+
+/// The environment variable that makes [`impls_snapshot!`] write the current
+/// state instead of comparing against it.
+///
+/// Any value other than `0` enables updating.
+///
+/// [`impls_snapshot!`]: ../macro.impls_snapshot.html
+pub const UPDATE_VAR: &str = "IMPLS_UPDATE";
+
+/// Returns `true` if [`UPDATE_VAR`] requests updating snapshots.
+///
+/// [`UPDATE_VAR`]: constant.UPDATE_VAR.html
+pub fn update_requested() -> bool {
+    match _std::env::var_os(UPDATE_VAR) {
+        Some(value) => value != "0",
+        None => false,
+    }
+}
+
+/// Renders a [`Matrix`](../matrix/struct.Matrix.html) in the snapshot file
+/// format.
+#[derive(Clone, Copy, Debug)]
+pub struct Snapshot<'a>(Matrix<'a>);
+
+impl<'a> Snapshot<'a> {
+    /// Creates a snapshot of `matrix`.
+    pub fn new(matrix: Matrix<'a>) -> Self {
+        Snapshot(matrix)
+    }
+}
+
+impl<'a> fmt::Display for Snapshot<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let matrix = self.0;
+        for (row, cells) in matrix.rows.iter().zip(matrix.cells) {
+            writeln!(f, "{}", Label(row))?;
+            for (column, &value) in matrix.columns.iter().zip(cells.iter()) {
+                let value = if value { "yes" } else { "no" };
+                writeln!(f, "  {}: {}", Label(column), value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A difference between a snapshot file and the current state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The type as written.
+    pub ty: String,
+    /// The trait expression as written.
+    pub expr: String,
+    /// The value in the snapshot file, or `None` if it was not recorded.
+    pub old: Option<bool>,
+    /// The current value, or `None` if it is no longer checked.
+    pub new: Option<bool>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn cell(value: bool) -> &'static str {
+            if value {
+                "yes"
+            } else {
+                "no"
+            }
+        }
+
+        match (self.old, self.new) {
+            (Some(false), Some(true)) => {
+                write!(f, "gained  {}: {}", self.ty, self.expr)
+            }
+            (Some(true), Some(false)) => {
+                write!(f, "lost    {}: {}", self.ty, self.expr)
+            }
+            (None, Some(new)) => {
+                write!(f, "added   {}: {} = {}", self.ty, self.expr, cell(new))
+            }
+            (Some(old), None) => {
+                write!(f, "removed {}: {} = {}", self.ty, self.expr, cell(old))
+            }
+            _ => write!(f, "same    {}: {}", self.ty, self.expr),
+        }
+    }
+}
+
+/// The failure of [`check`](fn.check.html).
+#[derive(Debug)]
+pub enum Error {
+    /// The snapshot file does not exist.
+    Missing(PathBuf),
+    /// The snapshot file differs from the current state.
+    Changed(PathBuf, Vec<Change>),
+    /// The snapshot file could not be read or written.
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Missing(ref path) => write!(
+                f,
+                "trait snapshot `{}` does not exist\n\
+                 rerun with {}=1 to create it",
+                path.display(),
+                UPDATE_VAR,
+            ),
+            Error::Changed(ref path, ref changes) => {
+                writeln!(
+                    f,
+                    "trait snapshot `{}` does not match:",
+                    path.display(),
+                )?;
+                for change in changes {
+                    writeln!(f, "  {}", change)?;
+                }
+                write!(f, "rerun with {}=1 to accept the changes", UPDATE_VAR)
+            }
+            Error::Io(ref path, ref error) => write!(
+                f,
+                "failed to access trait snapshot `{}`: {}",
+                path.display(),
+                error,
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(_, ref error) => Some(error),
+            Error::Missing(_) | Error::Changed(..) => None,
+        }
+    }
+}
+
+/// Parses the entries of a snapshot file as `(type, expression, value)`.
+///
+/// Lines that are blank or not understood are ignored.
+fn parse(text: &str) -> Vec<(&str, &str, bool)> {
+    let mut entries = Vec::new();
+    let mut ty = None;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            ty = Some(line.trim());
+            continue;
+        }
+
+        // Values never contain ':', unlike paths in expressions.
+        let mut parts = line.trim().rsplitn(2, ':');
+        let value = match parts.next().map(str::trim) {
+            Some("yes") => true,
+            Some("no") => false,
+            _ => continue,
+        };
+        if let (Some(ty), Some(expr)) = (ty, parts.next()) {
+            entries.push((ty, expr.trim(), value));
+        }
+    }
+    entries
+}
+
+/// Returns the differences between the snapshot text `old` and `new`, in the
+/// order of `new` followed by removed entries.
+pub fn diff(old: &str, new: &str) -> Vec<Change> {
+    let old = parse(old);
+    let new = parse(new);
+
+    let find = |entries: &[(&str, &str, bool)], ty: &str, expr: &str| {
+        entries
+            .iter()
+            .find(|&&(t, e, _)| t == ty && e == expr)
+            .map(|&(_, _, value)| value)
+    };
+    let change = |ty: &str, expr: &str, old, new| Change {
+        ty: ty.to_string(),
+        expr: expr.to_string(),
+        old,
+        new,
+    };
+
+    let mut changes = Vec::new();
+    for &(ty, expr, value) in &new {
+        let old_value = find(&old, ty, expr);
+        if old_value != Some(value) {
+            changes.push(change(ty, expr, old_value, Some(value)));
+        }
+    }
+    for &(ty, expr, value) in &old {
+        if find(&new, ty, expr).is_none() {
+            changes.push(change(ty, expr, Some(value), None));
+        }
+    }
+    changes
+}
+
+/// Compares `matrix` against the snapshot file at `path`.
+///
+/// If `update` is `true`, the file is written with the current state instead
+/// of failing when it is missing or differs.
+pub fn check(path: &Path, matrix: &Matrix, update: bool) -> Result<(), Error> {
+    let new = Snapshot::new(*matrix).to_string();
+    let io_error = |error| Error::Io(path.to_path_buf(), error);
+
+    let changes = match fs::read_to_string(path) {
+        Ok(old) => {
+            if old == new {
+                return Ok(());
+            }
+            diff(&old, &new)
+        }
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => {
+            if !update {
+                return Err(Error::Missing(path.to_path_buf()));
+            }
+            Vec::new()
+        }
+        Err(error) => return Err(io_error(error)),
+    };
+
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(path, new).map_err(io_error)
+    } else if changes.is_empty() {
+        // Only formatting differs.
+        Ok(())
+    } else {
+        Err(Error::Changed(path.to_path_buf(), changes))
+    }
+}
+
+/// Checks that types implement the same traits as recorded in a snapshot file.
+///
+/// The path is relative to the directory of the calling crate's `Cargo.toml`.
+/// Traits are either a list of logical trait expressions, as in
+/// [`impls_matrix!`], or `catalog` for the [trait catalog](catalog/index.html).
+///
+/// This panics with every gained, lost, added, and removed trait if the file
+/// differs from the current state. Setting the [`IMPLS_UPDATE`] environment
+/// variable to `1` writes the current state instead, which is also how the
+/// file is created:
+///
+/// ```sh
+/// IMPLS_UPDATE=1 cargo test
+/// ```
+///
+/// This requires the `std` feature. Because the types are checked in a
+/// constant, they must not depend on generic parameters.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[macro_use] extern crate impls;
+/// pub struct Client { /* ... */ }
+/// pub struct Config { /* ... */ }
+///
+/// #[test]
+/// fn api_traits() {
+///     impls_snapshot!("tests/snapshots/api.txt", [Client, Config]: catalog);
+///     impls_snapshot!("tests/snapshots/send.txt", [Client]: [Send & Sync]);
+/// }
+/// # fn main() {}
+/// ```
+///
+/// If `Client` stops being `Sync`, the test fails with:
+///
+/// ```text
+/// trait snapshot `/path/to/crate/tests/snapshots/send.txt` does not match:
+///   lost    Client: Send & Sync
+/// rerun with IMPLS_UPDATE=1 to accept the changes
+/// ```
+///
+/// [`impls_matrix!`]: macro.impls_matrix.html
+/// [`IMPLS_UPDATE`]: snapshot/constant.UPDATE_VAR.html
+#[macro_export(local_inner_macros)]
+macro_rules! impls_snapshot {
+    ($path:expr, [$($type:ty),+ $(,)?]: $($traits:tt)+) => {{
+        let path = $crate::_std::path::Path::new(
+            _impls_env!("CARGO_MANIFEST_DIR"),
+        )
+        .join($path);
+        let matrix = impls_matrix!([$($type),+]: $($traits)+);
+        let update = $crate::snapshot::update_requested();
+
+        if let $crate::_core::result::Result::Err(error) =
+            $crate::snapshot::check(&path, matrix, update)
+        {
+            $crate::_core::panic!("{}", error);
+        }
+    }};
+}
@@ -0,0 +1,145 @@
+//! Sweeps of a logical trait expression over const values and arities.
+//!
+//! Use [`impls_sweep!`] to create a [`Sweep`].
+//!
+//! [`impls_sweep!`]: ../macro.impls_sweep.html
+//! [`Sweep`]: struct.Sweep.html
+
+use _core::fmt;
+
+/// The value of a logical trait expression over a range of const values or
+/// arities, returned by [`impls_sweep!`].
+///
+/// Its [`Display`] implementation lists the ranges of values over which the
+/// expression stays the same:
+///
+/// ```text
+/// [u8; N]: Default
+///   N in 0..=32: true
+///   N in 33..=64: false
+/// ```
+///
+/// [`impls_sweep!`]: ../macro.impls_sweep.html
+/// [`Display`]: https://doc.rust-lang.org/core/fmt/trait.Display.html
+#[derive(Clone, Copy, Debug)]
+pub struct Sweep<'a> {
+    /// The swept type as written, such as `[u8; N]` or `tuples(u8)`.
+    pub ty: &'static str,
+    /// The logical trait expression as written.
+    pub expr: &'static str,
+    /// The name of the swept variable, or `arity` for tuples and function
+    /// pointers.
+    pub var: &'static str,
+    /// The value of the expression at each point, in order.
+    pub points: &'a [Point],
+}
+
+/// The value of a logical trait expression at one point of a [`Sweep`].
+///
+/// [`Sweep`]: struct.Sweep.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    /// The const value or arity.
+    pub value: i128,
+    /// Whether the type implements the expression.
+    pub implements: bool,
+}
+
+/// A range of values over which an expression has the same value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    /// The first value.
+    pub start: i128,
+    /// The last value, inclusive.
+    pub end: i128,
+    /// Whether the type implements the expression over the range.
+    pub implements: bool,
+}
+
+impl<'a> Sweep<'a> {
+    /// Returns whether the type implements the expression at `value`, or
+    /// `None` if it was not swept.
+    pub fn get(&self, value: i128) -> Option<bool> {
+        self.points
+            .iter()
+            .find(|point| point.value == value)
+            .map(|point| point.implements)
+    }
+
+    /// Returns an iterator over the maximal ranges of values over which the
+    /// expression has the same value.
+    pub fn runs(&self) -> Runs<'a> {
+        Runs {
+            points: self.points,
+        }
+    }
+
+    /// Returns an iterator over the values at which the expression changes
+    /// from the value before them.
+    pub fn changes(&self) -> Changes<'a> {
+        let mut runs = self.runs();
+        runs.next();
+        Changes { runs }
+    }
+}
+
+impl<'a> fmt::Display for Sweep<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.ty, self.expr)?;
+        for run in self.runs() {
+            if run.start == run.end {
+                write!(f, "\n  {} = {}", self.var, run.start)?;
+            } else {
+                write!(f, "\n  {} in {}..={}", self.var, run.start, run.end)?;
+            }
+            write!(f, ": {}", run.implements)?;
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the runs of a [`Sweep`].
+///
+/// [`Sweep`]: struct.Sweep.html
+#[derive(Clone, Debug)]
+pub struct Runs<'a> {
+    points: &'a [Point],
+}
+
+impl<'a> Iterator for Runs<'a> {
+    type Item = Run;
+
+    fn next(&mut self) -> Option<Run> {
+        let first = *self.points.first()?;
+        let len = self
+            .points
+            .iter()
+            .position(|point| point.implements != first.implements)
+            .unwrap_or(self.points.len());
+
+        let last = self.points[len - 1];
+        self.points = &self.points[len..];
+        Some(Run {
+            start: first.value,
+            end: last.value,
+            implements: first.implements,
+        })
+    }
+}
+
+/// An iterator over the values at which the expression of a [`Sweep`]
+/// changes.
+///
+/// [`Sweep`]: struct.Sweep.html
+#[derive(Clone, Debug)]
+pub struct Changes<'a> {
+    runs: Runs<'a>,
+}
+
+impl<'a> Iterator for Changes<'a> {
+    type Item = i128;
+
+    fn next(&mut self) -> Option<i128> {
+        self.runs.next().map(|run| run.start)
+    }
+}
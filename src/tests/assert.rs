@@ -0,0 +1,33 @@
+use explain::Report;
+
+#[test]
+fn passes() {
+    assert_impls_rt!(String: Clone & !Copy & Send & Sync);
+    assert_impls_rt!(u8: (From<u16>) | (Into<u16>));
+    debug_assert_impls!(&mut u8: !Copy & !(Clone | Copy));
+}
+
+#[test]
+#[should_panic(expected = "Copy  = false")]
+fn fails() {
+    assert_impls_rt!(String: Clone & Copy & Send);
+}
+
+#[test]
+fn report() {
+    let root = _impls!(@node String: Clone & !(Copy | Send) ^ From<u8>);
+    let report =
+        Report::new("String", "Clone & !(Copy | Send) ^ From<u8>", &root);
+
+    assert!(!root.value());
+    assert_eq!(
+        report.to_string(),
+        "assertion failed: `String: Clone & !(Copy | Send) ^ From<u8>`
+  Clone    = true
+  Copy     = false
+  Send     = true
+  From<u8> = false
+  parsed: Clone & (!(Copy | Send) ^ From<u8>)
+    with: true & (!(false | true) ^ false) = false"
+    );
+}
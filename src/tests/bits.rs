@@ -0,0 +1,45 @@
+use std::{cell::Cell, rc::Rc};
+
+const RC_MASK: u64 = impls_mask!(Rc<u8>: [Copy, Clone, Send, Sync, Default]);
+const STRING_COUNT: usize = impls_count!(String: [Copy, Clone, Send, Sync]);
+const CELL_FIRST: Option<usize> = impls_first!(Cell<u8>: [Sync, Send, Copy]);
+
+#[test]
+fn mask() {
+    assert_eq!(RC_MASK, 0b10010);
+    assert_eq!(impls_mask!(u8: [Copy & Send, !Send, From<u16>]), 0b001);
+    assert_eq!(impls_mask!(u16: [From<u8>, PartialEq<u16>]), 0b11);
+    assert_eq!(impls_mask!(str: []), 0);
+}
+
+#[test]
+fn mask_64() {
+    let mask = impls_mask!(u8: [
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+        Copy, Copy, Copy, Copy, Copy, Copy, Copy, Copy,
+    ]);
+    assert_eq!(mask, !0);
+}
+
+#[test]
+fn count() {
+    assert_eq!(STRING_COUNT, 3);
+    assert_eq!(impls_count!(str: [Sized, Clone]), 0);
+    assert_eq!(impls_count!(u8: [Copy | Sized, Into<u16>,]), 2);
+    assert_eq!(impls_count!(u8: []), 0);
+}
+
+#[test]
+fn first() {
+    assert_eq!(CELL_FIRST, Some(1));
+    assert_eq!(impls_first!(u8: [Copy]), Some(0));
+    assert_eq!(impls_first!(Cell<u8>: [Sync, Iterator]), None);
+    assert_eq!(impls_first!(u8: [From<u16>, PartialOrd<u8>]), Some(1));
+    assert_eq!(impls_first!(u8: []), None);
+}
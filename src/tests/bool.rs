@@ -1,14 +0,0 @@
-pub struct True;
-pub struct False;
-
-impl True {
-    pub const fn value(self) -> bool {
-        true
-    }
-}
-
-impl False {
-    pub const fn value(self) -> bool {
-        false
-    }
-}
@@ -0,0 +1,20 @@
+use catalog::Group;
+
+#[test]
+fn profile() {
+    let profile = trait_profile!(str);
+
+    assert_eq!(profile.ty, "str");
+    assert_eq!(profile.get("Sized"), Some(false));
+    assert_eq!(profile.get("AsRef<[u8]>"), Some(true));
+    assert_eq!(profile.get("Add"), Some(false));
+    assert_eq!(profile.get("Unknown"), None);
+
+    let groups = profile.entries.iter().map(|e| e.group);
+    assert!(groups.clone().take_while(|&g| g == Group::Core).count() > 50);
+    assert!(groups.collect::<Vec<_>>().windows(2).all(|g| g[0] <= g[1]));
+
+    let profile = trait_profile!(u32);
+    assert!(profile.implemented().any(|t| t == "BitXor"));
+    assert!(profile.implemented().any(|t| t == "Into<i64>"));
+}
@@ -0,0 +1,68 @@
+use tests::{compact, compact_all};
+
+trait Id {}
+impl Id for u64 {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct UserId(u64);
+
+#[test]
+fn diff() {
+    let diff = impls_diff!(u64, UserId; Id, From<u64> | From<u32>);
+
+    assert!(!diff.is_same());
+    assert_eq!(diff.only_b().count(), 0);
+    // Skip traits from `alloc` and `std`, which depend on features, and
+    // `LowerExp` and `UpperExp`, which integers implement since Rust 1.42.
+    let only_a: Vec<_> = diff
+        .only_a()
+        .filter(|&name| name != "LowerExp" && name != "UpperExp")
+        .collect();
+    assert_eq!(
+        compact_all(&only_a[only_a.len() - 2..]),
+        compact_all(&["Id", "From<u64> | From<u32>"])
+    );
+    assert_eq!(
+        compact_all(&only_a[..22]),
+        compact_all(&[
+            "Copy",
+            "Default",
+            "PartialOrd",
+            "Ord",
+            "Display",
+            "Binary",
+            "Octal",
+            "LowerHex",
+            "UpperHex",
+            "FromStr",
+            "Into<u64>",
+            "Into<u128>",
+            "Into<i128>",
+            "Add",
+            "Sub",
+            "Mul",
+            "Div",
+            "Rem",
+            "Not",
+            "BitAnd",
+            "BitOr",
+            "BitXor",
+        ])
+    );
+    let text = compact(diff.to_string());
+    assert!(text.starts_with(&compact(
+        "`u64` vs `UserId`:
+  only `u64`: Copy, Default, PartialOrd, Ord, Display, Binary,"
+    )));
+    assert!(text.contains(&compact(
+        "
+  only `UserId`: (none)
+  both: Send, Sync, Unpin, Sized, Any, Clone, Debug, PartialEq, Eq, Hash"
+    )));
+}
+
+#[test]
+#[should_panic(expected = "only `UserId`: (none)")]
+fn assert_same() {
+    assert_same_traits!(u64, UserId);
+}
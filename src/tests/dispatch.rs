@@ -0,0 +1,101 @@
+use std::fmt::Debug;
+use std::rc::Rc;
+
+struct Opaque;
+
+#[test]
+fn select() {
+    let describe = if_impls!(5u8: Debug + Send => |v| format!("{:?}", v),
+        else => |_| "<opaque>".into());
+    assert_eq!(describe, "5");
+
+    let fallback = String::from("<opaque>");
+    let describe = if_impls!(Opaque: Debug => |v| format!("{:?}", v),
+        else => |_| fallback.clone());
+    assert_eq!(describe, fallback);
+}
+
+#[test]
+fn bounds() {
+    let eq = if_impls!(7u8: PartialEq<u8> => |v| *v == 7, else => |_| false);
+    assert!(eq);
+
+    let sent = if_impls!(Rc::new(1): Send => |_| true, else => |_| false);
+    assert!(!sent);
+}
+
+#[test]
+fn captures() {
+    let mut seen = Vec::new();
+    if_impls!(vec![1, 2]: Debug => |v| seen.push(format!("{:?}", v)));
+    if_impls!(Opaque: Debug => |v| seen.push(format!("{:?}", v)));
+    assert_eq!(seen, ["[1, 2]"]);
+
+    let mut ran = false;
+    if_impls!(Opaque: Debug => |v| println!("{:?}", v),
+        else => |_| ran = true,);
+    assert!(ran);
+
+    let len = if_impls!(vec![1, 2]: Debug => |_| 0, else => |v| v.len());
+    assert_eq!(len, 0);
+}
+
+#[test]
+fn generic_bounds() {
+    let copy = if_impls!(5u8: Clone + Default + Debug => fn |v| -> String {
+        format!("{:?}", v.clone())
+    }, else => |_| String::new());
+    assert_eq!(copy, "5");
+
+    let copy = if_impls!(Opaque: Clone => fn |v| -> u8 {
+        drop(v.clone());
+        1
+    }, else => |_| 0);
+    assert_eq!(copy, 0);
+
+    let mut ran = false;
+    if_impls!(String::new(): Clone => fn |v| drop(v.clone()),
+        else => |_| ran = true);
+    assert!(!ran);
+}
+
+#[test]
+fn try_as_dyn() {
+    use std::fmt::{Display, Write};
+
+    let text = "text";
+    assert_eq!(
+        try_as_dyn!(&text => dyn Display + Sync)
+            .unwrap()
+            .to_string(),
+        "text"
+    );
+    assert!(try_as_dyn!(&Opaque => dyn Debug).is_none());
+    assert!(try_as_dyn!(&5u8 => dyn PartialEq<u8>).is_some());
+
+    let mut out = String::new();
+    try_as_dyn!(&mut out => dyn Write)
+        .unwrap()
+        .write_str("a")
+        .unwrap();
+    assert!(try_as_dyn!(&mut Opaque => dyn Write).is_none());
+    assert_eq!(out, "a");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn try_as_dyn_box() {
+    use std::any::Any;
+
+    let boxed = Box::new(Rc::new(1)) as Box<Rc<i32>>;
+    match try_as_dyn!(boxed => Box<dyn Send>) {
+        Ok(_) => panic!("`Rc` is `Send`"),
+        Err(boxed) => assert_eq!(**boxed, 1),
+    }
+
+    let any = try_as_dyn!(Box::new(1u8) => Box<dyn Any + Send>).unwrap();
+    assert_eq!(any.downcast_ref::<u8>(), Some(&1));
+
+    let eq = try_as_dyn!(Box::new(1u8) => Box<dyn PartialEq<u8>>).unwrap();
+    assert!(*eq == 1);
+}
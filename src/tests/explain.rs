@@ -0,0 +1,115 @@
+use explain::Node;
+use tests::{compact, compact_all};
+
+#[test]
+fn tree() {
+    let explanation = impls_explain!(
+        &'static str: Send & !(Copy | From<&'static str>) ^ Clone;
+        From< &'static str > => "conversion",
+    );
+
+    assert!(explanation.value());
+    assert_eq!(
+        compact(explanation.to_string()),
+        compact(
+            "&'static str: Send & !(Copy | From<&'static str>) ^ Clone = true
+and = true
+├── Send = true
+└── xor = true
+    ├── not = false
+    │   └── or = true
+    │       ├── Copy = true
+    │       └── From<&'static str> = true (conversion)
+    └── Clone = true"
+        )
+    );
+
+    match *explanation.root {
+        Node::And(a, _) => assert_eq!(a.name(), "Send"),
+        _ => panic!("expected `and` at the root"),
+    }
+}
+
+#[test]
+fn type_args() {
+    use std::{cell::Cell, collections::HashMap, rc::Rc, sync::Arc};
+
+    let explanation = explain_type!(
+        Arc<HashMap<&'static str, (Rc<u8>, [Cell<u8>; 2])>>: Send & Sync
+    );
+
+    assert!(!explanation.value());
+    assert_eq!(
+        compact(explanation.to_string()),
+        compact(
+            "Arc<HashMap<&'static str, (Rc<u8>, [Cell<u8>; 2])>>: Send & Sync = false
+└── HashMap<&'static str, (Rc<u8>, [Cell<u8>; 2])> = false
+    ├── &'static str = true
+    │   └── str = true
+    └── (Rc<u8>, [Cell<u8>; 2]) = false
+        ├── Rc<u8> = false (flips)
+        │   └── u8 = true
+        └── [Cell<u8>; 2] = false
+            └── Cell<u8> = false (flips)
+                └── u8 = true"
+        )
+    );
+
+    let mut flips = Vec::new();
+    explanation.for_each_flip_point(|node| flips.push(node.ty));
+    assert_eq!(compact_all(&flips), compact_all(&["Rc<u8>", "Cell<u8>"]));
+
+    // Lifetimes and consts are skipped, and associated types are walked.
+    let explanation =
+        explain_type!(Box<dyn Iterator<Item = u8> + 'static>: !Copy);
+    assert!(explanation.value());
+    assert_eq!(explanation.root.args.len(), 1);
+    assert!(explanation.root.args[0].args.is_empty());
+
+    let explanation = explain_type!(Option<&'static mut Vec<*const u8>>: Send);
+    assert_eq!(
+        compact(explanation.to_string()),
+        compact(
+            "Option<&'static mut Vec<*const u8>>: Send = false
+└── &'static mut Vec<*const u8> = false
+    └── Vec<*const u8> = false
+        └── *const u8 = false (flips)
+            └── u8 = true"
+        )
+    );
+}
+
+#[test]
+fn type_args_without_flips() {
+    let explanation = explain_type!(Vec<u8>: Send);
+
+    assert_eq!(
+        compact(explanation.to_string()),
+        compact("Vec<u8>: Send = true\n└── u8 = true")
+    );
+
+    let mut flips = 0;
+    explanation.for_each_flip_point(|_| flips += 1);
+    assert_eq!(flips, 0);
+}
+
+#[test]
+fn type_args_flip_outside_differing_subtrees() {
+    let explanation = explain_type!(Option<&'static Vec<u8>>: Copy);
+
+    // `u8` has the same value as the whole type, but only within `Vec<u8>`,
+    // which does not.
+    assert_eq!(
+        compact(explanation.to_string()),
+        compact(
+            "Option<&'static Vec<u8>>: Copy = true
+└── &'static Vec<u8> = true (flips)
+    └── Vec<u8> = false
+        └── u8 = true"
+        )
+    );
+
+    let mut flips = Vec::new();
+    explanation.for_each_flip_point(|node| flips.push(node.ty));
+    assert_eq!(compact_all(&flips), compact_all(&["&'static Vec<u8>"]));
+}
@@ -0,0 +1,83 @@
+use std::fmt::Debug;
+
+trait Describe {}
+
+struct Point;
+
+impl Describe for Point {}
+impl<T: Describe + ?Sized> Describe for &T {}
+
+#[test]
+fn sized() {
+    let forms = impls_forms!(Point: Describe);
+
+    assert_eq!(forms.ty(), "Point");
+    assert_eq!(forms.expr(), "Describe");
+    assert!(forms.implements());
+    assert_eq!(forms.get("&Point"), Some(true));
+    assert_eq!(forms.get("&mut Point"), Some(false));
+    assert_eq!(forms.get("Point"), Some(true));
+    assert!(forms.missing().all(|form| form != "&Point"));
+}
+
+#[test]
+fn unsized_types() {
+    let forms = impls_forms!(dyn Describe + Send: Describe & !Sized);
+    assert_eq!(forms.get("&(dyn Describe + Send)"), Some(false));
+    assert_eq!(forms.get("Option<dyn Describe + Send>"), None);
+
+    let forms = impls_forms!(str: Debug);
+    assert!(forms.forms().all(|(_, implements)| implements));
+
+    let forms = impls_forms!([u8]: Debug);
+    assert_eq!(forms.get("&[u8]"), Some(true));
+    assert_eq!(forms.get("[[u8]]"), None);
+}
+
+#[test]
+fn explicitly_unsized() {
+    use std::path::Path;
+
+    let forms = impls_forms!(?Sized Path: Debug);
+    assert_eq!(forms.ty(), "Path");
+    assert!(forms.implements());
+    assert_eq!(forms.get("&Path"), Some(true));
+    assert_eq!(forms.get("&mut Path"), Some(true));
+    assert_eq!(forms.get("Option<Path>"), None);
+    assert_eq!(forms.get("[Path]"), None);
+}
+
+#[test]
+fn arrays() {
+    let forms = impls_forms!([u8; 4]: Copy);
+
+    assert!(forms.implements());
+    assert_eq!(forms.get("[[u8; 4]; 1]"), Some(true));
+    assert_eq!(forms.get("&mut [u8; 4]"), Some(false));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc() {
+    let forms = impls_forms!(Point: Describe);
+
+    assert_eq!(
+        forms.matrix.text().to_string(),
+        "              | Describe
+--------------+---------
+Point         | yes
+&Point        | yes
+&mut Point    | no
+Option<Point> | no
+[Point; 1]    | no
+[Point]       | no
+Box<Point>    | no
+Rc<Point>     | no
+Arc<Point>    | no
+Vec<Point>    | no"
+    );
+
+    let forms = impls_forms!(dyn Describe: Describe);
+    assert_eq!(forms.matrix.rows.len(), 6);
+    assert_eq!(forms.get("Box<dyn Describe>"), Some(false));
+}
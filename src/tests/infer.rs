@@ -0,0 +1,70 @@
+use std::{cell::Cell, marker::PhantomData, rc::Rc};
+use tests::compact;
+
+struct Handle<T, U>(Rc<T>, PhantomData<U>);
+
+unsafe impl<T: Clone, U> Send for Handle<T, U> {}
+
+#[test]
+fn minimal_sets() {
+    let bounds = infer_bounds!(
+        Handle<_, _>: Send & !Sync over {Send, Sync, Clone}
+    );
+    assert_eq!(
+        compact(bounds.to_string()),
+        compact(
+            "Handle<_, _>: Send & !Sync
+  parameter 1: {Clone}
+  parameter 2: {}"
+        )
+    );
+    assert_eq!(bounds.candidates, ["Send", "Sync", "Clone"]);
+
+    let sets: Vec<u64> = bounds.minimal(0).unwrap().map(|s| s.mask()).collect();
+    assert_eq!(sets, [0b100]);
+    assert!(bounds.params[1].is_minimal(0));
+    assert!(bounds.minimal(2).is_none());
+
+    let bounds = infer_bounds!(Cell<_>: Sync over {Sync});
+    assert_eq!(
+        compact(bounds.to_string()),
+        compact("Cell<_>: Sync\n  parameter 1: unsatisfiable")
+    );
+}
+
+#[test]
+fn nested_and_subtraits() {
+    let bounds = infer_bounds!(
+        (_, [Option<_>; 2]): Sync | Copy over {Sync, Clone, Copy}
+    );
+    assert_eq!(
+        compact(bounds.to_string()),
+        compact(
+            "(_, [Option<_>; 2]): Sync | Copy
+  parameter 1: {Sync} or {Clone, Copy}
+  parameter 2: {Sync} or {Clone, Copy}"
+        )
+    );
+
+    let bounds = infer_bounds!((_, Box<_>, _): Send over {Send, Sync});
+    assert_eq!(
+        compact(bounds.to_string()),
+        compact(
+            "(_, Box<_>, _): Send
+  parameter 1: {Send}
+  parameter 2: {Send}
+  parameter 3: {Send}"
+        )
+    );
+
+    // `Copy` alone cannot be implemented, so it never satisfies `Copy`.
+    assert!(!bounds.params[0].is_satisfied_by(0b100));
+
+    let bounds = infer_bounds!(
+        Vec<_>: PartialEq<Vec<u8>> over {PartialEq<u8>}
+    );
+    assert_eq!(
+        compact(bounds.to_string()),
+        compact("Vec<_>: PartialEq<Vec<u8>>\n  parameter 1: {PartialEq<u8>}")
+    );
+}
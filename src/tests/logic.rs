@@ -0,0 +1,49 @@
+use logic::{And, Bool, False, If, IsTrue, Not, Or, True, Xor};
+use std::cell::Cell;
+
+fn value<B: Bool>() -> bool {
+    B::VALUE
+}
+
+#[test]
+fn operators() {
+    assert!(!value::<<True as Not>::Output>());
+    assert!(value::<<False as Not>::Output>());
+
+    assert!(value::<<True as And<True>>::Output>());
+    assert!(!value::<<True as And<False>>::Output>());
+    assert!(!value::<<False as And<True>>::Output>());
+
+    assert!(value::<<False as Or<True>>::Output>());
+    assert!(!value::<<False as Or<False>>::Output>());
+
+    assert!(!value::<<True as Xor<True>>::Output>());
+    assert!(value::<<True as Xor<False>>::Output>());
+    assert!(!value::<<False as Xor<False>>::Output>());
+}
+
+#[test]
+fn impls_ty() {
+    assert!(value::<impls_ty!(u8: Copy & (Send | !Sync))>());
+    assert!(!value::<impls_ty!(Cell<u8>: Sync)>());
+
+    type Text = <impls_ty!(String: Clone) as If<str, [u8]>>::Output;
+    let text: &Text = "text";
+    assert_eq!(text.len(), 4);
+}
+
+trait Shared {
+    const THREADS: usize;
+}
+
+impl Shared for String
+where
+    impls_ty!(String: Send & Sync): IsTrue,
+{
+    const THREADS: usize = 8;
+}
+
+#[test]
+fn where_clause() {
+    assert_eq!(<String as Shared>::THREADS, 8);
+}
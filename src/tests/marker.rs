@@ -0,0 +1,48 @@
+use std::{cell::Cell, mem, rc::Rc};
+use tests::compact;
+
+trait_marker!(CloneM = Clone);
+trait_marker!(pub(crate) ThreadSafe = Send + Sync);
+trait_marker!(
+    /// Types that compare with `u8`.
+    EqU8 = PartialEq<u8>
+);
+
+fn requires_thread_safe<T: ThreadSafe>(_: T) {}
+
+#[test]
+fn evaluates_through_impls() {
+    assert!(impls!(u8: CloneM & ThreadSafe));
+    assert!(impls!(Rc<u8>: CloneM & !ThreadSafe));
+    assert!(impls!(Cell<u8>: !ThreadSafe));
+    assert!(impls!(u8: EqU8));
+    assert!(!impls!(u16: EqU8));
+    assert!(impls!(str: !CloneM));
+}
+
+#[test]
+fn evaluates_through_matrix() {
+    let matrix = impls_matrix!([u8, Rc<u8>]: [CloneM, ThreadSafe]);
+
+    assert_eq!(matrix.columns, ["CloneM", "ThreadSafe"]);
+    assert_eq!(matrix.get(0, 1), Some(true));
+    assert_eq!(matrix.get(1, 0), Some(true));
+    assert_eq!(matrix.get(1, 1), Some(false));
+}
+
+#[test]
+fn bounds_in_where_clause() {
+    requires_thread_safe(String::new());
+}
+
+#[test]
+fn values() {
+    assert_eq!(mem::size_of_val(&CloneM), 0);
+    assert_eq!(CloneM.bounds(), "Clone");
+    assert_eq!(ThreadSafe.bounds(), "Send + Sync");
+    assert_eq!(compact(EqU8.bounds()), "PartialEq<u8>");
+    assert_eq!(format!("{:?}", ThreadSafe), r#"Marker("Send + Sync")"#);
+
+    let copy = CloneM;
+    assert_eq!(copy.bounds(), CloneM.bounds());
+}
@@ -0,0 +1,95 @@
+use std::rc::Rc;
+use tests::{compact, compact_all};
+
+trait Pair<A, B> {}
+impl Pair<u8, u16> for u32 {}
+
+#[test]
+fn split() {
+    let matrix = impls_matrix!(
+        [u32, Rc<Vec<u8>>, &'static str,]:
+        [Pair<u8, u16>, Copy | !Send, (Clone), From<Rc<Vec<u8>>> ^ Sync,]
+    );
+
+    assert_eq!(
+        compact_all(matrix.columns),
+        compact_all(&[
+            "Pair<u8, u16>",
+            "Copy | !Send",
+            "(Clone)",
+            "From<Rc<Vec<u8>>> ^ Sync"
+        ])
+    );
+    assert_eq!(
+        compact_all(matrix.rows),
+        compact_all(&["u32", "Rc<Vec<u8>>", "&'static str"])
+    );
+    assert_eq!(
+        matrix.cells,
+        [
+            [true, true, true, true],
+            [false, true, true, true],
+            [false, true, true, true],
+        ]
+    );
+    assert_eq!(matrix.get(3, 0), None);
+}
+
+#[test]
+fn escape() {
+    let matrix = impls_matrix!([u8]: [Send | Sync]);
+
+    assert!(matrix.markdown().to_string().contains("`Send \\| Sync`"));
+}
+
+#[test]
+fn text() {
+    let matrix = impls_matrix!([String, Rc<u8>]: [Send, Clone & !Copy]);
+
+    assert_eq!(
+        compact(matrix.text().to_string()),
+        compact(
+            "       | Send | Clone & !Copy
+-------+------+--------------
+String | yes  | yes
+Rc<u8> | no   | yes"
+        )
+    );
+}
+
+#[test]
+fn relation() {
+    let relation = relation_matrix!(std::ops::Add, [u8, u16, f32]);
+
+    assert_eq!(compact(relation.name), "std::ops::Add");
+    assert_eq!(relation.types(), ["u8", "u16", "f32"]);
+    assert_eq!(
+        compact_all(relation.matrix.columns),
+        [
+            "std::ops::Add<u8>",
+            "std::ops::Add<u16>",
+            "std::ops::Add<f32>"
+        ]
+    );
+    assert_eq!(
+        relation.matrix.cells,
+        [
+            [true, false, false],
+            [false, true, false],
+            [false, false, true]
+        ]
+    );
+    assert_eq!(
+        compact(relation.dot().to_string()),
+        compact(
+            "digraph \"std::ops::Add\" {
+    \"u8\";
+    \"u16\";
+    \"f32\";
+}"
+        )
+    );
+
+    let relation = relation_matrix!(PartialEq, [&'static str, String]);
+    assert_eq!(relation.matrix.cells, [[true, true], [true, true]]);
+}
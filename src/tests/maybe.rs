@@ -0,0 +1,56 @@
+use std::rc::Rc;
+
+struct Opaque;
+
+#[test]
+fn debug() {
+    assert_eq!(format!("{:?}", maybe_debug!(Rc::new(1))), "1");
+    assert_eq!(format!("{:#?}", maybe_debug!((1,))), "(\n    1,\n)");
+
+    let opaque = maybe_debug!(Opaque);
+    assert!(!opaque.implements());
+
+    let opaque = opaque.with_placeholder("{} { .. }");
+    let name = opaque.type_name();
+    assert_eq!(format!("{:?}", opaque), format!("{} {{ .. }}", name));
+}
+
+#[cfg(feature = "type_name")]
+#[test]
+fn type_name() {
+    let opaque = maybe_debug!(Opaque);
+    assert_eq!(opaque.type_name(), "impls::tests::maybe::Opaque");
+    assert_eq!(format!("{:?}", opaque), "<impls::tests::maybe::Opaque>");
+
+    let opaque = opaque.with_placeholder("{}: {}");
+    assert_eq!(
+        format!("{:?}", opaque),
+        "impls::tests::maybe::Opaque: impls::tests::maybe::Opaque"
+    );
+}
+
+#[cfg(not(feature = "type_name"))]
+#[test]
+fn type_name() {
+    let opaque = maybe_debug!(Opaque);
+    assert_eq!(opaque.type_name(), "_");
+    assert_eq!(format!("{:?}", opaque), "<_>");
+}
+
+#[test]
+fn display() {
+    let name = String::from("impls");
+    assert!(maybe_display!(name).implements());
+    assert_eq!(format!("{:>6}", maybe_display!(name)), " impls");
+
+    let placeholder = String::from("?");
+    let text = maybe_display!(Opaque).with_placeholder(&placeholder);
+    assert_eq!(text.to_string(), "?");
+}
+
+#[test]
+fn temporaries() {
+    let text =
+        format!("{} {:?}", maybe_display!(1 + 1), maybe_debug!(vec![()]));
+    assert_eq!(text, "2 [()]");
+}
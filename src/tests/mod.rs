@@ -1,6 +1,22 @@
 #![allow(dead_code)]
 
-mod bool;
+mod assert;
+mod bits;
+mod catalog;
+mod diff;
+mod dispatch;
+mod explain;
+mod forms;
+mod infer;
+mod logic;
+mod marker;
+mod matrix;
+mod maybe;
+mod pair;
+mod select;
+#[cfg(feature = "std")]
+mod snapshot;
+mod witness;
 
 #[derive(Clone)]
 struct Test;
@@ -8,6 +24,20 @@ struct Test;
 #[derive(Clone)]
 struct Box<T>(T);
 
+/// Removes spaces and dashes from text that includes the output of
+/// `stringify!`, whose spacing differs between compiler versions.
+fn compact<S: AsRef<str>>(text: S) -> String {
+    text.as_ref()
+        .chars()
+        .filter(|&c| c != ' ' && c != '-')
+        .collect()
+}
+
+/// Applies `compact` to each label.
+fn compact_all<S: AsRef<str>>(labels: &[S]) -> Vec<String> {
+    labels.iter().map(compact).collect()
+}
+
 trait True {}
 impl<T: ?Sized> True for T {}
 trait False {}
@@ -20,7 +50,7 @@ trait False {}
 fn precedence() {
     macro_rules! table {
         ($($a:ident, $b:ident, $c:ident, $d:ident;)+) => { $({
-            const IMPLS: bool = bool::$a.value() | bool::$b.value() ^ bool::$c.value() & bool::$d.value();
+            const IMPLS: bool = ::logic::$a.value() | ::logic::$b.value() ^ ::logic::$c.value() & ::logic::$d.value();
 
             assert_eq!(impls!(Test:   $a |  $b  ^  $c  & $d),   IMPLS);
             assert_eq!(impls!(Test:   $a | ($b  ^ ($c  & $d))), IMPLS);
@@ -0,0 +1,45 @@
+use std::ops::Add;
+
+struct Meters(f64);
+
+impl Add<f64> for Meters {
+    type Output = Meters;
+
+    fn add(self, rhs: f64) -> Meters {
+        Meters(self.0 + rhs)
+    }
+}
+
+#[test]
+fn symmetric() {
+    let pair = symmetric!(Meters, f64: Add);
+
+    assert_eq!(pair.property, "symmetry over `Add`");
+    assert_eq!(
+        pair.to_string(),
+        "`Meters` and `f64` fail symmetry over `Add`:
+  Meters: Add<f64> = true
+  f64: Add<Meters> = false (missing)"
+    );
+
+    assert!(symmetric!(f64, f64: std::ops::Add).holds());
+}
+
+#[test]
+fn total_order() {
+    let pair = total_order_consistent!(Meters, f64);
+
+    assert_eq!(pair.bounds.len(), 4);
+    assert_eq!(pair.missing().count(), 4);
+    assert!(total_order_consistent!(String, String).holds());
+
+    let pair = total_order_consistent!(String, &'static str);
+    assert_eq!(
+        pair.to_string(),
+        "`String` and `&'static str` fail consistent ordering:
+  String: PartialEq<&'static str> = true
+  &'static str: PartialEq<String> = true
+  String: PartialOrd<&'static str> = false (missing)
+  &'static str: PartialOrd<String> = false (missing)"
+    );
+}
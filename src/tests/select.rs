@@ -0,0 +1,75 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+trait Strategy {
+    const CHUNK: usize;
+}
+
+impl Strategy for Rc<u8> {
+    const CHUNK: usize = impls_select!(Rc<u8> {
+        Copy => 64,
+        Send | Sync => 32,
+        Clone => 16,
+        _ => 1,
+    });
+}
+
+#[test]
+fn first_match() {
+    assert_eq!(<Rc<u8>>::CHUNK, 16);
+
+    const CELL: &str = impls_select!(Cell<u8> { Sync => "sync", _ => "local" });
+    assert_eq!(CELL, "local");
+
+    let only_default = impls_select!(u8 { _ => "default" });
+    assert_eq!(only_default, "default");
+}
+
+#[test]
+fn expressions() {
+    let value = impls_select!(u8 {
+        (From<u32>) | Into<bool> => 1,
+        !Copy ^ (Clone) => 2,
+        Copy & !Send => 3,
+        _ => 4,
+    });
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn values() {
+    const NONE: Option<&str> = impls_select!(String {
+        Copy => Some("copy"),
+        Send & !Sync => Some("send"),
+        _ => None,
+    });
+    assert_eq!(NONE, None);
+
+    let base = 10;
+    let value = impls_select!(String { Clone => base + 2, _ => base * 2 });
+    assert_eq!(value, 12);
+}
+
+#[test]
+fn type_if() {
+    use std::mem::size_of;
+
+    type Small = impls_type_if!(u8: Copy & !Send => u64, else => u8);
+    assert_eq!(size_of::<Small>(), 1);
+
+    type Counter = impls_type_if!(Cell<u8>: Sync => u8, else => Cell<u16>);
+    let counter: Counter = Cell::new(300);
+    assert_eq!(counter.get(), 300);
+
+    type Text = impls_type_if!(String: Clone => str, else => [u8]);
+    let text: &Text = "text";
+    assert_eq!(text.len(), 4);
+}
+
+#[test]
+fn type_if_generic_branches() {
+    type Pair<T> = impls_type_if!(u8: Copy => (T, T), else => T);
+
+    let pair: Pair<&str> = ("a", "b");
+    assert_eq!(pair.1, "b");
+}
@@ -0,0 +1,79 @@
+use snapshot::{check, diff, Change, Error};
+use std::{env, fs, path::PathBuf, process};
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir()
+        .join(format!("impls-snapshot-{}", process::id()))
+        .join(name)
+}
+
+#[test]
+fn update_and_compare() {
+    let path = temp_path("update.txt");
+    let _ = fs::remove_file(&path);
+
+    let old = impls_matrix!([String, u8]: [Send, Copy]);
+    match check(&path, old, false) {
+        Err(Error::Missing(ref p)) if *p == path => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    check(&path, old, true).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "String\n  Send: yes\n  Copy: no\nu8\n  Send: yes\n  Copy: yes\n",
+    );
+    check(&path, old, false).unwrap();
+
+    let new = impls_matrix!([String, u8]: [Send, Clone ^ Copy]);
+    let error = check(&path, new, false).unwrap_err();
+    assert_eq!(
+        error
+            .to_string()
+            .replace(&path.display().to_string(), "PATH"),
+        "\
+trait snapshot `PATH` does not match:
+  added   String: Clone ^ Copy = yes
+  added   u8: Clone ^ Copy = no
+  removed String: Copy = no
+  removed u8: Copy = yes
+rerun with IMPLS_UPDATE=1 to accept the changes",
+    );
+
+    check(&path, new, true).unwrap();
+    check(&path, new, false).unwrap();
+
+    // Absolute paths are used as is.
+    let path_str = path.to_str().unwrap();
+    impls_snapshot!(path_str, [String, u8]: [Send, Clone ^ Copy]);
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn gained_and_lost() {
+    let old = "Foo\n  Send: yes\n  Iterator<Item = u8>: no\n";
+    let new = "Foo\n  Send: no\n  Iterator<Item = u8>: yes\n";
+
+    let changes = diff(old, new);
+    assert_eq!(
+        changes,
+        [
+            Change {
+                ty: "Foo".to_string(),
+                expr: "Send".to_string(),
+                old: Some(true),
+                new: Some(false),
+            },
+            Change {
+                ty: "Foo".to_string(),
+                expr: "Iterator<Item = u8>".to_string(),
+                old: Some(false),
+                new: Some(true),
+            },
+        ]
+    );
+    assert_eq!(changes[0].to_string(), "lost    Foo: Send");
+    assert_eq!(changes[1].to_string(), "gained  Foo: Iterator<Item = u8>");
+
+    assert!(diff(old, &old.replace("\n", "\n\n")).is_empty());
+}
@@ -0,0 +1,64 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, HashMap},
+    fmt::{Debug, Display},
+    hash::Hash,
+    panic::{RefUnwindSafe, UnwindSafe},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+trait Marker {}
+
+struct Tagged<T>(T);
+
+impl<T: Marker> Clone for Tagged<T> {
+    fn clone(&self) -> Self {
+        unreachable!()
+    }
+}
+
+#[test]
+fn auto_traits() {
+    assert!(impls!(for<T> T: !Send & !Sync & !Unpin));
+    assert!(impls!(for<T> T: !UnwindSafe & !RefUnwindSafe));
+    assert!(impls!(for<T: Send + Sync + Unpin> T: Send & Sync & Unpin));
+    assert!(impls!(for<T: UnwindSafe + RefUnwindSafe> T: UnwindSafe));
+
+    assert!(impls!(for<T: Send> Arc<T>: !Send & !Sync));
+    assert!(impls!(for<T: Send + Sync> Arc<T>: Send & Sync));
+    assert!(impls!(for<T: Send> Mutex<T>: Send & Sync));
+    assert!(impls!(for<T: Send + Sync> Cell<T>: Send & !Sync));
+    assert!(impls!(for<T> Box<T>: Unpin & !Send));
+}
+
+#[test]
+fn conditional_impls() {
+    assert!(impls!(for<T: Clone> Vec<T>: Clone & !Copy & !Debug));
+    assert!(impls!(for<T> Vec<T>: !Clone & Default));
+    assert!(impls!(for<T: Clone> Rc<T>: Clone));
+    assert!(impls!(for<T> Rc<T>: Clone));
+    assert!(impls!(for<T: Clone + Copy, U: Clone> (T, U): Clone & !Copy));
+    assert!(impls!(for<T: Debug + Display> [T; 4]: Debug & !Display));
+
+    assert!(impls!(
+        for<K: Eq + PartialEq + Hash, V: Clone> HashMap<K, V>: !Clone
+    ));
+    assert!(impls!(
+        for<K: Clone + Eq + PartialEq + Hash, V: Clone> HashMap<K, V>: Clone
+    ));
+    assert!(impls!(
+        for<T: Ord + Eq + PartialOrd + PartialEq> BTreeSet<T>: Ord & Hash
+            | !Hash
+    ));
+    assert!(impls!(for<T: PartialEq<u8>> T: PartialEq<u8> & !PartialEq));
+    assert!(impls!(for<T: PartialEq<Vec<u8>>> T: PartialEq<Vec<u8>>));
+}
+
+#[test]
+fn marker_traits() {
+    assert!(impls!(for<T: Marker> Tagged<T>: Clone));
+    assert!(impls!(for<T: Send> Tagged<T>: !Clone));
+
+    const _: [(); 1] = [(); impls!(for<T: Marker> Tagged<T>: Clone) as usize];
+}